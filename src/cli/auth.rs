@@ -2,11 +2,117 @@ use fie::config;
 use fie::api;
 use fie::api::http::{self, GlobalRequest, Request, matsu};
 use serde_derive::{Deserialize};
+use tokio::net::TcpListener;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 use std::io::{self, Write};
+use std::net::Ipv4Addr;
+use std::time::Duration;
 use std::collections::HashMap;
+use std::path::PathBuf;
 
-pub async fn twitter(mut config: config::Twitter) {
+use crate::config::Credentials;
+
+///Twitter is given generous time to redirect, as user has to authorize fie first.
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+///Waits for Twitter to redirect browser to local `listener`, and returns value of `result` parameter.
+///
+///Redirect must contain parameter `check` with value `expected`, to be sure that it belongs to ongoing authorization.
+async fn twitter_callback(mut listener: TcpListener, check: &str, expected: &str, result: &str) -> Option<String> {
+    const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+    loop {
+        let (mut stream, _) = match matsu!(listener.accept()) {
+            Ok(stream) => stream,
+            Err(error) => {
+                eprintln!("Failed to accept callback connection. Error: {}", error);
+                return None;
+            }
+        };
+
+        let mut buffer = Vec::new();
+        let mut chunk = [0u8; 1024];
+        while !buffer.ends_with(b"\r\n\r\n") && buffer.len() < MAX_REQUEST_SIZE {
+            match matsu!(stream.read(&mut chunk)) {
+                Ok(0) | Err(_) => break,
+                Ok(size) => buffer.extend_from_slice(&chunk[..size]),
+            }
+        }
+
+        //Request line is `GET /callback?oauth_token=...&oauth_verifier=... HTTP/1.1`
+        let request = String::from_utf8_lossy(&buffer);
+        let target = request.lines().next().and_then(|line| line.split(' ').nth(1)).unwrap_or("");
+        let (path, query) = match target.find('?') {
+            Some(idx) => (&target[..idx], &target[idx+1..]),
+            None => (target, ""),
+        };
+
+        let params: HashMap<String, String> = match path {
+            "/callback" => yukikaze::serde_urlencoded::from_str(query).unwrap_or_default(),
+            //Browser may ask for other things like favicon
+            _ => {
+                let _ = matsu!(stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"));
+                continue;
+            }
+        };
+
+        //OAuth 1.0a uses `denied`, while OAuth 2.0 uses `error`
+        let (result, message) = match (params.get(check), params.get(result)) {
+            (Some(value), Some(result)) if value == expected => (Some(result.to_owned()), "fie is authorized. You can close this page."),
+            _ if params.contains_key("denied") || params.contains_key("error") => (None, "Authorization is denied."),
+            _ => {
+                let _ = matsu!(stream.write_all(b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"));
+                continue;
+            }
+        };
+
+        let response = format!("HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", message.len(), message);
+        let _ = matsu!(stream.write_all(response.as_bytes()));
+
+        if result.is_none() {
+            eprintln!("{}", message);
+        }
+        break result;
+    }
+}
+
+///Reads PIN, that Twitter shows after authorization.
+fn twitter_pin() -> String {
+    let mut buffer = String::new();
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    loop {
+        buffer.truncate(0);
+
+        let _ = stdout.write_all(b"Print: ");
+        let _ = stdout.flush();
+        match stdin.read_line(&mut buffer) {
+            Ok(_) => (),
+            Err(_) => {
+                let _ = stdout.write_all(b"Failed to read input. Try again...\n");
+                continue;
+            }
+        }
+
+        let pin = buffer.trim();
+        match pin.parse::<u32>() {
+            Ok(_) => break pin.to_owned(),
+            Err(_) => {
+                let _ = stdout.write_all(b"Invalid PIN specified, should contain only digits. Try again...\n");
+                continue;
+            }
+        }
+    }
+}
+
+///Performs authorization with Twitter.
+///
+///If `loopback` port is specified, Twitter redirects to `http://127.0.0.1:<port>/callback` once authorized,
+///otherwise user is asked to enter PIN.
+pub async fn twitter(mut config: config::Twitter, loopback: Option<u16>) -> Option<Credentials> {
     const REQUEST_TOKEN_URI: &str = "https://api.twitter.com/oauth/request_token";
     const ACCESS_TOKEN_URI: &str = "https://api.twitter.com/oauth/access_token";
 
@@ -23,9 +129,20 @@ pub async fn twitter(mut config: config::Twitter) {
 
     http::set_timeout(&Default::default());
 
+    let (listener, callback) = match loopback {
+        Some(port) => match matsu!(TcpListener::bind((Ipv4Addr::LOCALHOST, port))) {
+            Ok(listener) => (Some(listener), format!("http://{}:{}/callback", Ipv4Addr::LOCALHOST, port)),
+            Err(error) => {
+                eprintln!("Unable to listen on port {}. Error: {}", port, error);
+                return None;
+            }
+        },
+        None => (None, "oob".to_owned()),
+    };
+
     let (auth_params, auth_header) = {
         let mut auth_params = HashMap::new();
-        auth_params.insert("oauth_callback", "oob");
+        auth_params.insert("oauth_callback", callback.as_str());
         auth_params.insert("x_auth_access_type", "write");
         (auth_params.clone(), oauth.gen_auth(&http::Method::POST, REQUEST_TOKEN_URI, auth_params))
     };
@@ -41,7 +158,7 @@ pub async fn twitter(mut config: config::Twitter) {
         Ok(req) => req,
         Err(_) => {
             eprintln!("Request timed out");
-            return;
+            return None;
         }
     };
 
@@ -52,54 +169,40 @@ pub async fn twitter(mut config: config::Twitter) {
                     Ok(response) => response,
                     Err(error) => {
                         eprintln!("Unable to parse response with request token. Error: {}", error);
-                        return;
+                        return None;
                     }
                 },
                 Err(error) => {
                     eprintln!("Failed to read response with requested token. Error: {}", error);
-                    return;
+                    return None;
                 }
             },
             false => {
                 eprintln!("Request for token failed with {}", response.status());
-                return;
+                return None;
             }
         },
         Err(error) => {
             eprintln!("Failed to request ouath token :( Error: {}", error);
-            return;
+            return None;
         }
     };
 
-    println!("Please use following link to authroize fie:\nhttps://api.twitter.com/oauth/authorize?oauth_token={}", request_token.oauth_token);
-    println!("Once done please enter PIN...");
-    let pin = {
-        let mut buffer = String::new();
-        let stdin = io::stdin();
-        let stdout = io::stdout();
-        let mut stdout = stdout.lock();
-
-        loop {
-            buffer.truncate(0);
-
-            let _ = stdout.write_all(b"Print: ");
-            let _ = stdout.flush();
-            match stdin.read_line(&mut buffer) {
-                Ok(_) => (),
-                Err(_) => {
-                    let _ = stdout.write_all(b"Failed to read input. Try again...\n");
-                    continue;
-                }
-            }
-
-            let pin = buffer.trim();
-            match pin.parse::<u32>() {
-                Ok(_) => break pin.to_owned(),
+    println!("Please use following link to authorize fie:\nhttps://api.twitter.com/oauth/authorize?oauth_token={}", request_token.oauth_token);
+    let pin = match listener {
+        Some(listener) => {
+            println!("Waiting for Twitter to redirect to {}...", callback);
+            match matsu!(tokio::time::timeout(CALLBACK_TIMEOUT, twitter_callback(listener, "oauth_token", &request_token.oauth_token, "oauth_verifier"))) {
+                Ok(verifier) => verifier?,
                 Err(_) => {
-                    let _ = stdout.write_all(b"Invalid PIN specified, should contain only digits. Try again...\n");
-                    continue;
+                    eprintln!("Twitter hasn't redirected in time");
+                    return None;
                 }
             }
+        },
+        None => {
+            println!("Once done please enter PIN...");
+            twitter_pin()
         }
     };
 
@@ -121,7 +224,7 @@ pub async fn twitter(mut config: config::Twitter) {
         Ok(req) => req,
         Err(_) => {
             eprintln!("Request timed out");
-            return;
+            return None;
         }
     };
 
@@ -132,25 +235,316 @@ pub async fn twitter(mut config: config::Twitter) {
                     Ok(response) => response,
                     Err(error) => {
                         eprintln!("Unable to parse response with access token. Error: {}", error);
-                        return;
+                        return None;
                     }
                 },
                 Err(error) => {
                     eprintln!("Failed to read response with access token. Error: {}", error);
-                    return;
+                    return None;
                 }
             },
             false => {
                 eprintln!("Request for access token failed with {}", response.status());
-                return;
+                return None;
             }
         },
         Err(error) => {
             eprintln!("Failed to request access token :( Error: {}", error);
-            return;
+            return None;
+        }
+    };
+
+    println!("Received access token successfully.");
+    Some(Credentials {
+        table: &["api", "twitter", "access"],
+        values: vec![("key", access_token.oauth_token), ("secret", access_token.oauth_token_secret)],
+        removed: &[],
+    })
+}
+
+///Performs OAuth 2.0 authorization with Twitter for API v2.
+///
+///Twitter redirects to `http://127.0.0.1:<port>/callback` once authorized.
+///Obtained tokens are stored in `token_path`.
+pub async fn twitter_v2(config: config::Twitter, port: u16, token_path: io::Result<PathBuf>) -> Option<Credentials> {
+    use api::twitter::{Twitter, data::Pkce};
+
+    if config.client_id.is_empty() {
+        eprintln!("OAuth 2.0 client ID is required. Specify it as api.twitter.client_id");
+        return None;
+    }
+
+    http::set_timeout(&Default::default());
+
+    let listener = match matsu!(TcpListener::bind((Ipv4Addr::LOCALHOST, port))) {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("Unable to listen on port {}. Error: {}", port, error);
+            return None;
+        }
+    };
+    let redirect_uri = format!("http://{}:{}/callback", Ipv4Addr::LOCALHOST, port);
+
+    let pkce = Pkce::new();
+    let state = {
+        use rand::{distributions, thread_rng, Rng};
+        thread_rng().sample_iter(&distributions::Alphanumeric).take(32).collect::<String>()
+    };
+
+    println!("Please use following link to authorize fie:\n{}", Twitter::authorize_url(&config.client_id, &redirect_uri, &state, &pkce));
+    println!("Waiting for Twitter to redirect to {}...", redirect_uri);
+    let code = match matsu!(tokio::time::timeout(CALLBACK_TIMEOUT, twitter_callback(listener, "state", &state, "code"))) {
+        Ok(code) => code?,
+        Err(_) => {
+            eprintln!("Twitter hasn't redirected in time");
+            return None;
+        }
+    };
+
+    let twitter = match matsu!(Twitter::login(&config.client_id, &config.client_secret, &code, &redirect_uri, &pkce)) {
+        Ok(twitter) => twitter,
+        Err(error) => {
+            eprintln!("{}", error);
+            return None;
         }
     };
 
-    println!("Received access token successfully.\nAdd following to your fie configuration file:");
-    println!("[api.twitter.access]\nkey = \"{}\"\nsecret = \"{}\"", access_token.oauth_token, access_token.oauth_token_secret);
+    println!("Received access token successfully.");
+    if let Some(token) = twitter.token() {
+        match token_path {
+            Ok(path) => match crate::config::save_to_file(&path, token) {
+                Ok(()) => println!("Tokens are stored in {}", path.display()),
+                Err(error) => eprintln!("Unable to store tokens: {}", error),
+            },
+            Err(error) => eprintln!("Unable to store tokens: {}", error),
+        }
+    }
+
+    Some(Credentials {
+        table: &["api", "twitter"],
+        values: vec![("version", "2".to_owned()), ("client_id", config.client_id)],
+        removed: &[],
+    })
+}
+
+///Asks user for yes/no answer, anything other than yes is considered no.
+pub fn confirm(prompt: &str) -> bool {
+    let mut buffer = String::new();
+
+    print!("{}", prompt);
+    let _ = io::stdout().flush();
+    match io::stdin().read_line(&mut buffer) {
+        Ok(_) => matches!(buffer.trim(), "y" | "Y" | "yes" | "Yes"),
+        Err(_) => false,
+    }
+}
+
+///Reads non-empty line from stdin.
+fn read_input(prompt: &str) -> String {
+    let mut buffer = String::new();
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    loop {
+        buffer.truncate(0);
+
+        let _ = stdout.write_all(prompt.as_bytes());
+        let _ = stdout.flush();
+        match stdin.read_line(&mut buffer) {
+            Ok(_) => (),
+            Err(_) => {
+                let _ = stdout.write_all(b"Failed to read input. Try again...\n");
+                continue;
+            }
+        }
+
+        match buffer.trim() {
+            "" => {
+                let _ = stdout.write_all(b"Input is empty. Try again...\n");
+                continue;
+            },
+            input => break input.to_owned(),
+        }
+    }
+}
+
+///Registers fie as application on Mastodon compatible server and performs OAuth authorization.
+///
+///Returns access token on success.
+async fn oauth_app(host: &str, scopes: &str) -> Option<String> {
+    const REDIRECT_URI: &str = "urn:ietf:wg:oauth:2.0:oob";
+
+    #[derive(Deserialize, Debug)]
+    struct AppRsp {
+        client_id: String,
+        client_secret: String,
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct TokenRsp {
+        access_token: String,
+    }
+
+    let req = Request::post(format!("https://{}/api/v1/apps", host)).expect("To create request")
+                                                                    .form(&[("client_name", "fie"), ("redirect_uris", REDIRECT_URI), ("scopes", scopes), ("website", env!("CARGO_PKG_REPOSITORY"))])
+                                                                    .expect("To serialize form params")
+                                                                    .global()
+                                                                    .send();
+
+    let req = match matsu!(req) {
+        Ok(req) => req,
+        Err(_) => {
+            eprintln!("Request timed out");
+            return None;
+        }
+    };
+
+    let app: AppRsp = match req {
+        Ok(mut response) => match response.is_success() {
+            true => match matsu!(response.json()) {
+                Ok(response) => response,
+                Err(error) => {
+                    eprintln!("Unable to parse response with application. Error: {}", error);
+                    return None;
+                }
+            },
+            false => {
+                eprintln!("Registration of application failed with {}", response.status());
+                return None;
+            }
+        },
+        Err(error) => {
+            eprintln!("Failed to register application :( Error: {}", error);
+            return None;
+        }
+    };
+
+    let query = yukikaze::serde_urlencoded::to_string([("client_id", app.client_id.as_str()), ("redirect_uri", REDIRECT_URI), ("response_type", "code"), ("scope", scopes)]).expect("To serialize query");
+    println!("Please use following link to authorize fie:\nhttps://{}/oauth/authorize?{}", host, query);
+    println!("Once done please enter authorization code...");
+    let code = read_input("Code: ");
+
+    let req = Request::post(format!("https://{}/oauth/token", host)).expect("To create request")
+                                                                    .form(&[("grant_type", "authorization_code"), ("code", code.as_str()), ("client_id", app.client_id.as_str()), ("client_secret", app.client_secret.as_str()), ("redirect_uri", REDIRECT_URI), ("scope", scopes)])
+                                                                    .expect("To serialize form params")
+                                                                    .global()
+                                                                    .send();
+
+    let req = match matsu!(req) {
+        Ok(req) => req,
+        Err(_) => {
+            eprintln!("Request timed out");
+            return None;
+        }
+    };
+
+    let access_token: TokenRsp = match req {
+        Ok(mut response) => match response.is_success() {
+            true => match matsu!(response.json()) {
+                Ok(response) => response,
+                Err(error) => {
+                    eprintln!("Unable to parse response with access token. Error: {}", error);
+                    return None;
+                }
+            },
+            false => {
+                eprintln!("Request for access token failed with {}", response.status());
+                return None;
+            }
+        },
+        Err(error) => {
+            eprintln!("Failed to request access token :( Error: {}", error);
+            return None;
+        }
+    };
+
+    Some(access_token.access_token)
+}
+
+pub async fn mastodon(host: String) -> Option<Credentials> {
+    //Only what fie uses: posting, media, timelines, notifications, favourites and bookmarks.
+    const SCOPES: &str = "read:accounts read:statuses read:notifications write:statuses write:media write:favourites write:bookmarks";
+
+    let host = host.trim_start_matches("https://").trim_start_matches("http://").trim_end_matches('/');
+
+    http::set_timeout(&Default::default());
+
+    let access_token = matsu!(oauth_app(host, SCOPES))?;
+    println!("Received access token successfully.");
+    Some(Credentials {
+        table: &["api", "mastodon"],
+        values: vec![("host", host.to_owned()), ("access_token", access_token)],
+        removed: &[],
+    })
+}
+
+pub async fn gab() -> Option<Credentials> {
+    const HOST: &str = "gab.com";
+    //Gab's fork has no bookmarks.
+    const SCOPES: &str = "read:accounts read:statuses read:notifications write:statuses write:media write:favourites";
+
+    http::set_timeout(&Default::default());
+
+    let access_token = matsu!(oauth_app(HOST, SCOPES))?;
+    println!("Received access token successfully.");
+    Some(Credentials {
+        table: &["api", "gab"],
+        values: vec![("access_token", access_token)],
+        removed: &[],
+    })
+}
+
+///Asks user for code of two-factor authentication.
+///
+///Returns `None` if nothing is entered, which aborts login.
+pub fn minds_two_factor() -> Option<String> {
+    let mut buffer = String::new();
+
+    print!("Minds requires two-factor authentication code: ");
+    let _ = io::stdout().flush();
+    match io::stdin().read_line(&mut buffer) {
+        Ok(_) => match buffer.trim() {
+            "" => None,
+            code => Some(code.to_owned()),
+        },
+        Err(_) => None,
+    }
+}
+
+pub async fn minds(token_path: io::Result<PathBuf>) -> Option<Credentials> {
+    http::set_timeout(&Default::default());
+
+    let username = read_input("Username: ");
+    let password = match rpassword::read_password_from_tty(Some("Password: ")) {
+        Ok(password) => password,
+        Err(error) => {
+            eprintln!("Failed to read password. Error: {}", error);
+            return None;
+        }
+    };
+
+    let minds = match matsu!(api::minds::Minds::login_with(&username, &password, minds_two_factor)) {
+        Ok(minds) => minds,
+        Err(error) => {
+            eprintln!("{}", error);
+            return None;
+        }
+    };
+
+    println!("Logged in successfully.");
+    match token_path {
+        Ok(path) => match crate::config::save_to_file(&path, minds.token()) {
+            Ok(()) => println!("Tokens are stored in {}", path.display()),
+            Err(error) => eprintln!("Unable to store tokens: {}", error),
+        },
+        Err(error) => eprintln!("Unable to store tokens: {}", error),
+    }
+
+    //Password is no longer needed, as tokens are refreshed on their own
+    Some(Credentials {
+        table: &["api", "minds"],
+        values: vec![("username", username)],
+        removed: &["password"],
+    })
 }
@@ -15,15 +15,16 @@ pub struct Args {
 
 impl Args {
     #[inline]
-    pub fn new(platforms: &mut Platforms) -> Self {
-        let args = Self::from_args();
+    pub fn new() -> Self {
+        Self::from_args()
+    }
 
+    #[inline]
+    pub fn apply_flags(&self, platforms: &mut Platforms) {
         //Unless user specifies manually, we use configuration defaults
-        if args.flags.twitter || args.flags.gab || args.flags.mastodon || args.flags.minds {
-            *platforms = unsafe { mem::transmute(args.flags) }
+        if self.flags.twitter || self.flags.gab || self.flags.mastodon || self.flags.minds {
+            *platforms = unsafe { mem::transmute::<Flags, Platforms>(self.flags) }
         }
-
-        args
     }
 }
 
@@ -48,6 +49,33 @@ pub enum Command {
     #[structopt(name = "post")]
     ///Creates new post.
     Post(Post),
+    #[structopt(name = "dm")]
+    ///Sends post as direct message.
+    Dm(Dm),
+    #[structopt(name = "edit")]
+    ///Edits already published post.
+    Edit(Edit),
+    #[structopt(name = "whoami")]
+    ///Verifies credentials and prints authenticated account of each social media.
+    Whoami,
+    #[structopt(name = "timeline")]
+    ///Shows home timeline of all social medias.
+    Timeline(Page),
+    #[structopt(name = "mentions")]
+    ///Shows posts that mention you on all social medias.
+    Mentions(Page),
+    #[structopt(name = "favourite")]
+    ///Favourites post. On Minds it votes up.
+    Favourite(PostRef),
+    #[structopt(name = "unfavourite")]
+    ///Removes post from favourites.
+    Unfavourite(PostRef),
+    #[structopt(name = "bookmark")]
+    ///Bookmarks post. Supported only by Mastodon.
+    Bookmark(PostRef),
+    #[structopt(name = "unbookmark")]
+    ///Removes post from bookmarks. Supported only by Mastodon.
+    Unbookmark(PostRef),
     #[structopt(name = "env")]
     ///Prints information about app environment.
     Env(Env),
@@ -57,6 +85,9 @@ pub enum Command {
     #[structopt(name = "auth")]
     ///Allows to perform authorization with social media.
     Auth(Auth),
+    #[structopt(name = "secrets")]
+    ///Manages encrypted secrets, that configuration can refer to.
+    Secrets(Secrets),
 }
 
 #[derive(Debug, StructOpt)]
@@ -67,7 +98,7 @@ pub struct Post {
     ///Adds hashtag at the last line of post.
     pub tags: Vec<String>,
     #[structopt(short = "i", long = "image")]
-    ///Adds image to post. Normally up to 4.
+    ///Adds image to post. Up to 4 images or single video. Use `-` to read from stdin.
     pub images: Vec<String>,
     #[structopt(short = "n", long = "nsfw")]
     ///Whether post is NSFW or not.
@@ -78,6 +109,11 @@ impl Into<fie::data::Post> for Post {
     fn into(self) -> fie::data::Post {
         let Post { message, tags, images, nsfw } = self;
 
+        let images = images.into_iter().map(|image| match image.as_str() {
+            "-" => fie::data::Attachment::reader("stdin", std::io::stdin()),
+            _ => fie::data::Attachment::file(image),
+        }).collect();
+
         fie::data::Post {
             message,
             tags,
@@ -89,6 +125,92 @@ impl Into<fie::data::Post> for Post {
     }
 }
 
+#[derive(Debug, StructOpt)]
+pub struct Dm {
+    ///Recipient's name in address book or handle to use on each social media.
+    pub recipient: String,
+    #[structopt(flatten)]
+    pub post: Post,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Edit {
+    #[structopt(long = "twitter-id")]
+    ///Id of Twitter's post to edit.
+    pub twitter: Option<String>,
+    #[structopt(long = "gab-id")]
+    ///Id of Gab's post to edit.
+    pub gab: Option<String>,
+    #[structopt(long = "mastodon-id")]
+    ///Id of Mastodon's post to edit.
+    pub mastodon: Option<String>,
+    #[structopt(long = "minds-id")]
+    ///Id of Minds's post to edit.
+    pub minds: Option<String>,
+    #[structopt(flatten)]
+    pub post: Post,
+}
+
+impl Edit {
+    pub fn into_parts(self) -> (fie::data::PostRefs, fie::data::Post) {
+        let Edit { twitter, gab, mastodon, minds, post } = self;
+
+        let refs = fie::data::PostRefs {
+            twitter: twitter.map(Into::into),
+            gab: gab.map(Into::into),
+            mastodon: mastodon.map(Into::into),
+            minds: minds.map(Into::into),
+        };
+
+        (refs, post.into())
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Page {
+    #[structopt(long = "max-id")]
+    ///Shows posts older than specified ID. Makes sense only with single social media.
+    pub max_id: Option<String>,
+    #[structopt(long = "since-id")]
+    ///Shows posts newer than specified ID. Makes sense only with single social media.
+    pub since_id: Option<String>,
+    #[structopt(short = "l", long = "limit")]
+    ///Maximum number of posts to fetch from each social media.
+    pub limit: Option<usize>,
+}
+
+impl From<Page> for fie::data::Page {
+    fn from(page: Page) -> Self {
+        let Page { max_id, since_id, limit } = page;
+
+        fie::data::Page {
+            max_id,
+            since_id,
+            limit,
+        }
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct PostRef {
+    #[structopt(possible_values = &["twitter", "gab", "mastodon", "minds"])]
+    ///Social media, where post is published.
+    pub platform: fie::data::Platform,
+    ///Post's ID
+    pub id: String,
+}
+
+impl From<PostRef> for fie::data::PostRef {
+    fn from(post: PostRef) -> Self {
+        let PostRef { platform, id } = post;
+
+        fie::data::PostRef {
+            platform,
+            id: id.into(),
+        }
+    }
+}
+
 #[derive(Debug, StructOpt)]
 pub struct Batch {
     ///TOML file that describes CLI arguments.
@@ -102,9 +224,64 @@ pub enum Env {
     Config,
 }
 
+#[derive(Debug, StructOpt)]
+pub struct AuthOutput {
+    #[structopt(long = "print")]
+    ///Prints credentials instead of writing them into configuration file.
+    pub print: bool,
+}
+
 #[derive(Debug, StructOpt)]
 pub enum Auth {
     #[structopt(name = "twitter")]
     ///Performs authorization with twitter
-    Twitter,
+    Twitter {
+        #[structopt(long = "loopback", value_name = "port")]
+        ///Receives authorization via local callback on specified port, instead of entering PIN.
+        loopback: Option<u16>,
+        #[structopt(long = "v2", requires = "loopback")]
+        ///Authorizes via OAuth 2.0 to use API v2, which supports only posting. Requires `--loopback`.
+        v2: bool,
+        #[structopt(flatten)]
+        output: AuthOutput,
+    },
+    #[structopt(name = "mastodon")]
+    ///Registers fie on Mastodon instance and performs authorization with it
+    Mastodon {
+        ///Host of Mastodon instance, e.g. mastodon.social
+        host: String,
+        #[structopt(flatten)]
+        output: AuthOutput,
+    },
+    #[structopt(name = "gab")]
+    ///Registers fie on Gab and performs authorization with it
+    Gab {
+        #[structopt(flatten)]
+        output: AuthOutput,
+    },
+    #[structopt(name = "minds")]
+    ///Logs in to Minds and stores tokens next to configuration file
+    Minds {
+        #[structopt(flatten)]
+        output: AuthOutput,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+pub enum Secrets {
+    #[structopt(name = "set")]
+    ///Sets secret, prompting for its value. Creates secrets file if needed.
+    Set {
+        ///Name of secret.
+        name: String,
+    },
+    #[structopt(name = "get")]
+    ///Prints value of secret.
+    Get {
+        ///Name of secret.
+        name: String,
+    },
+    #[structopt(name = "rotate")]
+    ///Re-encrypts secrets with new passphrase.
+    Rotate,
 }
@@ -1,11 +1,21 @@
 use std::path::{Path, PathBuf};
-use std::io::{self, Read};
+use std::io::{self, Read, Write};
 use std::fs;
 use std::env;
+use std::fmt;
+use std::process;
 
 use serde::de::{DeserializeOwned};
+use serde::Serialize;
 
 pub const NAME: &str = "fie.toml";
+pub const MINDS_TOKEN_NAME: &str = "minds_token.toml";
+pub const TWITTER_TOKEN_NAME: &str = "twitter_token.toml";
+pub const SECRETS_NAME: &str = "fie.secrets";
+pub const UPLOAD_CACHE_NAME: &str = "upload_cache.toml";
+
+///Fields, that may refer to value stored elsewhere instead of containing it.
+const REFERENCE_FIELDS: &[&str] = &["access_token", "password", "key", "secret", "client_id", "client_secret"];
 
 pub fn load_from_file<T: DeserializeOwned>(path: &Path) -> io::Result<T> {
     let mut file = fs::File::open(&path).map_err(|error| io::Error::new(io::ErrorKind::Other, format!("{}: {}", path.display(), error)))?;
@@ -14,6 +24,251 @@ pub fn load_from_file<T: DeserializeOwned>(path: &Path) -> io::Result<T> {
     toml::from_str(&buffer).map_err(|error| io::Error::new(io::ErrorKind::Other, format!("Invalid config: {}", error)))
 }
 
+///Writes value into file, readable only by owner.
+pub fn save_to_file<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    let content = toml::to_string(value).map_err(|error| io::Error::other(format!("Unable to serialize: {}", error)))?;
+    write_private(path, content.as_bytes())
+}
+
+///Writes content into file, readable only by owner.
+pub fn write_private(path: &Path, content: &[u8]) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path).map_err(|error| io::Error::other(format!("{}: {}", path.display(), error)))?;
+    file.write_all(content).map_err(|error| io::Error::other(format!("{}: {}", path.display(), error)))
+}
+
+///Replaces content of file, making it readable only by owner.
+///
+///Content is written into temporary file next to `path`, which is then renamed,
+///so file is never left half written.
+pub fn replace_private(path: &Path, content: &[u8]) -> io::Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    let temp = PathBuf::from(temp);
+
+    //Leftover of interrupted write may have different permissions
+    let _ = fs::remove_file(&temp);
+    write_private(&temp, content)?;
+    fs::rename(&temp, path).map_err(|error| io::Error::other(format!("{}: {}", path.display(), error)))
+}
+
+///Returns path to file with Minds's tokens, which is located next to configuration file.
+pub fn minds_token_path() -> io::Result<PathBuf> {
+    fie::config::Config::path().map(|path| path.with_file_name(MINDS_TOKEN_NAME))
+}
+
+///Credentials obtained by authorization.
+pub struct Credentials {
+    ///Path to table, e.g. `["api", "twitter", "access"]`.
+    pub table: &'static [&'static str],
+    pub values: Vec<(&'static str, String)>,
+    ///Keys that are no longer needed, e.g. password replaced by tokens.
+    pub removed: &'static [&'static str],
+}
+
+impl Credentials {
+    ///Writes credentials into `document`, keeping the rest of it intact.
+    fn merge(&self, document: &mut toml_edit::Document) -> io::Result<()> {
+        let mut table = document.as_table_mut();
+
+        for (idx, name) in self.table.iter().enumerate() {
+            let is_last = idx + 1 == self.table.len();
+            let entry = table.entry(name).or_insert_with(|| {
+                let mut table = toml_edit::Table::new();
+                //Intermediate tables are not written, unless they have own values
+                table.set_implicit(!is_last);
+                toml_edit::Item::Table(table)
+            });
+
+            table = match entry.as_table_mut() {
+                Some(table) => table,
+                None => return Err(io::Error::other(format!("Invalid config: '{}' is not a table", self.table[..=idx].join(".")))),
+            };
+        }
+
+        for (key, value) in self.values.iter() {
+            //Reference to secret, environment variable, file or command must not be replaced by plain text
+            if table.get(key).is_some_and(toml_edit::Item::is_table_like) {
+                return Err(io::Error::other(format!("'{}.{}' refers to value stored elsewhere. Update it there, or use --print to only print credentials", self.table.join("."), key)));
+            }
+
+            match table.get_mut(key).and_then(toml_edit::Item::as_value_mut) {
+                //Keep comments around old value
+                Some(old) => {
+                    let decor = old.decor().clone();
+                    *old = value.as_str().into();
+                    *old.decor_mut() = decor;
+                },
+                None => table[key] = toml_edit::value(value.as_str()),
+            }
+        }
+
+        for key in self.removed.iter() {
+            table.remove(key);
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Credentials {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let mut document = toml_edit::Document::new();
+        //Cannot fail on empty document
+        let _ = self.merge(&mut document);
+        fmt.write_str(document.to_string().trim_end())
+    }
+}
+
+///Merges credentials into configuration file, preserving its comments and formatting.
+///
+///Previous content of file is saved with `.bak` extension.
+///Returns path to backup.
+pub fn store_credentials(path: &Path, credentials: &Credentials) -> io::Result<PathBuf> {
+    let content = fs::read_to_string(path).map_err(|error| io::Error::other(format!("{}: {}", path.display(), error)))?;
+    let mut document = content.parse::<toml_edit::Document>().map_err(|error| io::Error::other(format!("Invalid config: {}", error)))?;
+
+    credentials.merge(&mut document)?;
+
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    let backup = PathBuf::from(backup);
+
+    fs::copy(path, &backup).map_err(|error| io::Error::other(format!("{}: {}", backup.display(), error)))?;
+    replace_private(path, document.to_string().as_bytes())?;
+
+    Ok(backup)
+}
+
+///Returns path to file with Twitter's OAuth 2.0 tokens, which is located next to configuration file.
+pub fn twitter_token_path() -> io::Result<PathBuf> {
+    fie::config::Config::path().map(|path| path.with_file_name(TWITTER_TOKEN_NAME))
+}
+
+///Returns path to cache of uploaded media, which is located next to configuration file.
+pub fn upload_cache_path() -> io::Result<PathBuf> {
+    fie::config::Config::path().map(|path| path.with_file_name(UPLOAD_CACHE_NAME))
+}
+
+///Returns path to encrypted secrets, which are located next to configuration file.
+pub fn secrets_path() -> io::Result<PathBuf> {
+    fie::config::Config::path().map(|path| path.with_file_name(SECRETS_NAME))
+}
+
+///Runs shell command and returns first line of its output.
+///
+///Only first line is used, as password managers like `pass` put additional information on next lines.
+fn run_command(command: &str) -> Result<String, String> {
+    #[cfg(windows)]
+    let output = process::Command::new("cmd").arg("/C").arg(command).stdin(process::Stdio::inherit()).stderr(process::Stdio::inherit()).output();
+    #[cfg(not(windows))]
+    let output = process::Command::new("sh").arg("-c").arg(command).stdin(process::Stdio::inherit()).stderr(process::Stdio::inherit()).output();
+
+    let output = output.map_err(|error| format!("unable to run command '{}': {}", command, error))?;
+    if !output.status.success() {
+        return Err(format!("command '{}' failed with {}", command, output.status));
+    }
+
+    match String::from_utf8(output.stdout) {
+        Ok(output) => Ok(output.lines().next().unwrap_or("").to_owned()),
+        Err(_) => Err(format!("command '{}' printed invalid unicode", command)),
+    }
+}
+
+///Replaces references in configuration with values, they refer to.
+///
+///Reference is table in place of string value, e.g. `password = { secret = "minds" }`.
+///Supported references:
+///
+///- `secret` - name of secret in encrypted secrets file;
+///- `env` - name of environment variable;
+///- `file` - path to file, without trailing newline;
+///- `command` - shell command, that prints value as first line of output.
+struct References {
+    secrets_path: PathBuf,
+    secrets: Option<fie::secrets::Secrets>,
+}
+
+impl References {
+    fn new(secrets_path: PathBuf) -> Self {
+        Self {
+            secrets_path,
+            secrets: None,
+        }
+    }
+
+    fn secret(&mut self, name: &str) -> io::Result<Option<&str>> {
+        if self.secrets.is_none() {
+            let passphrase = crate::secrets::passphrase()?;
+            self.secrets = Some(crate::secrets::load(&self.secrets_path, &passphrase)?);
+        }
+
+        Ok(self.secrets.as_ref().and_then(|secrets| secrets.get(name)))
+    }
+
+    fn resolve(&mut self, field: &str, reference: &toml::value::Table) -> io::Result<String> {
+        let error = |message: String| io::Error::other(format!("{}: {}", field, message));
+        let invalid = || io::Error::other(format!("Invalid config: {} should be string or reference like {{ secret = \"name\" }}, {{ env = \"NAME\" }}, {{ file = \"path\" }} or {{ command = \"cmd\" }}", field));
+
+        if reference.len() != 1 {
+            return Err(invalid());
+        }
+
+        let (kind, name) = match reference.iter().next() {
+            Some((kind, toml::Value::String(name))) => (kind.as_str(), name.as_str()),
+            _ => return Err(invalid()),
+        };
+
+        match kind {
+            "secret" => match self.secret(name)? {
+                Some(value) => Ok(value.to_owned()),
+                None => Err(error(format!("secret '{}' is not found in {}", name, self.secrets_path.display()))),
+            },
+            "env" => match env::var(name) {
+                Ok(value) => Ok(value),
+                Err(env::VarError::NotPresent) => Err(error(format!("environment variable '{}' is not set", name))),
+                Err(env::VarError::NotUnicode(_)) => Err(error(format!("environment variable '{}' is not valid unicode", name))),
+            },
+            "file" => match fs::read_to_string(name) {
+                //Files usually end with newline, which is not part of value
+                Ok(value) => Ok(value.trim_end_matches(&['\r', '\n'][..]).to_owned()),
+                Err(err) => Err(error(format!("unable to read file '{}': {}", name, err))),
+            },
+            "command" => run_command(name).map_err(error),
+            _ => Err(invalid()),
+        }
+    }
+
+    fn resolve_table(&mut self, prefix: &str, table: &mut toml::value::Table) -> io::Result<()> {
+        for (name, value) in table.iter_mut() {
+            let field = match prefix.len() {
+                0 => name.to_owned(),
+                _ => format!("{}.{}", prefix, name),
+            };
+
+            let resolved = match value {
+                toml::Value::Table(table) if REFERENCE_FIELDS.contains(&name.as_str()) => self.resolve(&field, table)?,
+                toml::Value::Table(table) => {
+                    self.resolve_table(&field, table)?;
+                    continue;
+                },
+                _ => continue,
+            };
+
+            *value = toml::Value::String(resolved);
+        }
+
+        Ok(())
+    }
+}
+
 pub trait FileSystemLoad: DeserializeOwned {
     fn path() -> io::Result<PathBuf> {
         match env::current_exe() {
@@ -46,4 +301,124 @@ pub trait FileSystemLoad: DeserializeOwned {
     }
 }
 
-impl FileSystemLoad for fie::config::Config {}
+impl FileSystemLoad for fie::config::Config {
+    fn load() -> io::Result<Self> {
+        let path = Self::path()?;
+        let mut config = match load_from_file(&path)? {
+            toml::Value::Table(config) => config,
+            _ => return Err(io::Error::other("Invalid config: should be table")),
+        };
+
+        References::new(path.with_file_name(SECRETS_NAME)).resolve_table("", &mut config)?;
+        toml::Value::Table(config).try_into().map_err(|error| io::Error::other(format!("Invalid config: {}", error)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn references() -> References {
+        let mut secrets = fie::secrets::Secrets::new();
+        secrets.set("minds_password".to_owned(), "minds secret".to_owned());
+
+        References {
+            secrets_path: PathBuf::from(SECRETS_NAME),
+            secrets: Some(secrets),
+        }
+    }
+
+    fn reference(kind: &str, name: &str) -> toml::value::Table {
+        let mut table = toml::value::Table::new();
+        table.insert(kind.to_owned(), toml::Value::String(name.to_owned()));
+        table
+    }
+
+    fn temp_file(name: &str, content: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("fie-{}-{}", process::id(), name));
+        fs::write(&path, content).expect("To write temporary file");
+        path
+    }
+
+    #[test]
+    fn should_resolve_references_in_config() {
+        let token_path = temp_file("gab_token", "file token\n");
+        env::set_var("FIE_TEST_MASTODON_TOKEN", "env token");
+
+        let mut config = format!(r#"
+            [api.minds]
+            username = "user"
+            password = {{ secret = "minds_password" }}
+            [api.gab]
+            access_token = {{ file = '{}' }}
+            [api.mastodon]
+            host = "mastodon.social"
+            access_token = {{ env = "FIE_TEST_MASTODON_TOKEN" }}
+            [api.twitter.access]
+            key = {{ command = "echo command key" }}
+            secret = "plain secret"
+            [api.twitter.consumer]
+            key = {{ env = "FIE_TEST_MASTODON_TOKEN" }}
+            [hooks]
+            username = {{ env = "FIE_TEST_MASTODON_TOKEN" }}
+        "#, token_path.display()).parse::<toml::Value>().expect("Valid TOML");
+
+        let result = references().resolve_table("", config.as_table_mut().unwrap());
+        let _ = fs::remove_file(&token_path);
+        result.expect("To resolve references");
+
+        let api = &config["api"];
+        assert_eq!(api["minds"]["username"].as_str(), Some("user"));
+        assert_eq!(api["minds"]["password"].as_str(), Some("minds secret"));
+        assert_eq!(api["gab"]["access_token"].as_str(), Some("file token"));
+        assert_eq!(api["mastodon"]["host"].as_str(), Some("mastodon.social"));
+        assert_eq!(api["mastodon"]["access_token"].as_str(), Some("env token"));
+        assert_eq!(api["twitter"]["access"]["key"].as_str(), Some("command key"));
+        assert_eq!(api["twitter"]["access"]["secret"].as_str(), Some("plain secret"));
+        assert_eq!(api["twitter"]["consumer"]["key"].as_str(), Some("env token"));
+        //Only credentials may be references, the rest of tables are left as they are
+        assert_eq!(config["hooks"]["username"].as_table(), Some(&reference("env", "FIE_TEST_MASTODON_TOKEN")));
+    }
+
+    #[test]
+    fn should_fail_to_resolve_missing_values() {
+        let mut references = references();
+
+        let error = references.resolve("api.minds.password", &reference("secret", "gab_token")).unwrap_err();
+        assert!(error.to_string().contains("secret 'gab_token' is not found"), "{}", error);
+
+        let error = references.resolve("api.gab.access_token", &reference("env", "FIE_TEST_MISSING_VARIABLE")).unwrap_err();
+        assert_eq!(error.to_string(), "api.gab.access_token: environment variable 'FIE_TEST_MISSING_VARIABLE' is not set");
+
+        let path = env::temp_dir().join(format!("fie-{}-missing", process::id()));
+        let error = references.resolve("api.gab.access_token", &reference("file", &path.to_string_lossy())).unwrap_err();
+        assert!(error.to_string().starts_with("api.gab.access_token: unable to read file"), "{}", error);
+    }
+
+    #[test]
+    fn should_reject_invalid_references() {
+        let mut references = references();
+
+        let mut two_kinds = reference("env", "HOME");
+        two_kinds.insert("file".to_owned(), toml::Value::String("path".to_owned()));
+        let mut not_string = toml::value::Table::new();
+        not_string.insert("env".to_owned(), toml::Value::Integer(1));
+
+        for table in [toml::value::Table::new(), two_kinds, not_string, reference("keyring", "name")].iter() {
+            let error = references.resolve("api.gab.access_token", table).unwrap_err();
+            assert!(error.to_string().starts_with("Invalid config: api.gab.access_token should be string or reference"), "{}", error);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_use_first_line_of_command_output() {
+        assert_eq!(run_command("printf 'first\\nsecond\\n'").as_deref(), Ok("first"));
+        assert_eq!(run_command("true").as_deref(), Ok(""));
+
+        let error = run_command("exit 3").unwrap_err();
+        assert!(error.starts_with("command 'exit 3' failed with"), "{}", error);
+        let error = run_command("printf '\\377'").unwrap_err();
+        assert_eq!(error, "command 'printf '\\377'' printed invalid unicode");
+    }
+}
@@ -5,6 +5,7 @@ use serde_derive::Deserialize;
 mod config;
 mod cli;
 mod auth;
+mod secrets;
 
 use fie::config::Config;
 use config::FileSystemLoad;
@@ -25,10 +26,28 @@ async fn create_api(config: Config) -> io::Result<fie::API> {
     }
 
     if config.platforms.twitter {
-        if let Err(error) = api.configure_twitter(config.api.twitter) {
+        let mut twitter = config.api.twitter;
+        let token_path = match twitter.version {
+            fie::config::TwitterVersion::V1 => None,
+            fie::config::TwitterVersion::V2 => Some(config::twitter_token_path()),
+        };
+        if let Some(Ok(path)) = token_path.as_ref() {
+            if path.exists() {
+                twitter.token = config::load_from_file(path).ok();
+            }
+        }
+
+        if let Err(error) = yukikaze::matsu!(api.configure_twitter(twitter)) {
             eprintln!("{}", error);
         } else {
-            any_enabled = true
+            any_enabled = true;
+
+            //Refresh replaces tokens
+            if let (Some(Ok(path)), Some(token)) = (token_path, api.twitter_token()) {
+                if let Err(error) = config::save_to_file(&path, token) {
+                    eprintln!("Unable to store Twitter token: {}", error);
+                }
+            }
         }
     }
 
@@ -41,10 +60,35 @@ async fn create_api(config: Config) -> io::Result<fie::API> {
     }
 
     if config.platforms.minds {
-        if let Err(error) = yukikaze::matsu!(api.configure_minds(config.api.minds)) {
+        let mut minds = config.api.minds;
+        let token_path = config::minds_token_path();
+        if let Ok(path) = token_path.as_ref() {
+            if path.exists() {
+                minds.token = config::load_from_file(path).ok();
+            }
+        }
+
+        if let Err(error) = yukikaze::matsu!(api.configure_minds_with(minds, auth::minds_two_factor)) {
             eprintln!("{}", error);
         } else {
-            any_enabled = true
+            any_enabled = true;
+
+            //Tokens are replaced on each login
+            if let (Ok(path), Some(token)) = (token_path, api.minds_token()) {
+                if let Err(error) = config::save_to_file(&path, token) {
+                    eprintln!("Unable to store Minds token: {}", error);
+                }
+            }
+        }
+    }
+
+    //Media can be re-used by subsequent runs, until it expires
+    if let Ok(path) = config::upload_cache_path() {
+        if path.exists() {
+            match config::load_from_file(&path) {
+                Ok(cache) => api.set_upload_cache(cache),
+                Err(error) => eprintln!("Unable to load upload cache: {}", error),
+            }
         }
     }
 
@@ -54,12 +98,20 @@ async fn create_api(config: Config) -> io::Result<fie::API> {
     }
 }
 
-fn handle_post_result(result: fie::api::PostResult) {
+fn store_upload_cache(api: &fie::API) {
+    if let Ok(path) = config::upload_cache_path() {
+        if let Err(error) = config::save_to_file(&path, &api.upload_cache()) {
+            eprintln!("Unable to store upload cache: {}", error);
+        }
+    }
+}
+
+fn handle_result<T, F: FnMut(&str, T)>(result: fie::api::ApiResult<T>, mut on_ok: F) {
     let (twitter, gab, mastodon, minds) = result.into_parts();
 
-    let handle_inner = |prefix, result| if let Some(result) = result {
+    let mut handle_inner = |prefix, result| if let Some(result) = result {
         match result {
-            Ok(id) => println!("{}(Id={})", prefix, id),
+            Ok(value) => on_ok(prefix, value),
             Err(error) => eprintln!("{}", error)
         }
     };
@@ -70,6 +122,10 @@ fn handle_post_result(result: fie::api::PostResult) {
     handle_inner("Minds", minds);
 }
 
+fn handle_post_result(result: fie::api::PostResult) {
+    handle_result(result, |prefix, id| println!("{}(Id={})", prefix, id));
+}
+
 #[derive(Deserialize, Debug)]
 pub struct Batch {
     post: Vec<fie::data::Post>,
@@ -98,7 +154,7 @@ fn use_twitter_builtin_consumer(twitter: &mut fie::config::Twitter) {
 
 #[inline(always)]
 fn runtime() -> tokio::runtime::Runtime {
-    tokio::runtime::Builder::new().basic_scheduler().enable_io().build().expect("To create async runtime")
+    tokio::runtime::Builder::new().basic_scheduler().enable_io().enable_time().build().expect("To create async runtime")
 }
 
 fn command_post(config: Config, post: cli::Post) -> io::Result<()> {
@@ -106,10 +162,129 @@ fn command_post(config: Config, post: cli::Post) -> io::Result<()> {
 
     let api = runtime.block_on(create_api(config))?;
     let result = runtime.block_on(api.send(post.into())).map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+    store_upload_cache(&api);
     handle_post_result(result);
     Ok(())
 }
 
+fn command_dm(mut config: Config, dm: cli::Dm) -> io::Result<()> {
+    let mut runtime = runtime();
+
+    let recipient = match config.recipients.remove(&dm.recipient) {
+        Some(recipient) => recipient,
+        None => fie::config::Recipient::new(&dm.recipient),
+    };
+
+    let api = runtime.block_on(create_api(config))?;
+    let result = runtime.block_on(api.send_direct(&recipient, dm.post.into())).map_err(io::Error::other)?;
+    store_upload_cache(&api);
+    handle_post_result(result);
+    Ok(())
+}
+
+fn command_edit(config: Config, edit: cli::Edit) -> io::Result<()> {
+    let mut runtime = runtime();
+
+    let (refs, post) = edit.into_parts();
+    let api = runtime.block_on(create_api(config))?;
+    let result = runtime.block_on(api.edit(refs, post)).map_err(io::Error::other)?;
+    store_upload_cache(&api);
+    handle_post_result(result);
+    Ok(())
+}
+
+fn command_whoami(config: Config) -> io::Result<()> {
+    let mut runtime = runtime();
+
+    let api = runtime.block_on(create_api(config))?;
+    let result = runtime.block_on(api.verify_credentials());
+    handle_result(result, |prefix, account| println!("{}: {}", prefix, account));
+    Ok(())
+}
+
+fn handle_timeline_result(result: fie::api::TimelineResult) {
+    let mut statuses = Vec::new();
+    let mut pages = Vec::new();
+
+    handle_result(result, |prefix, mut timeline| {
+        statuses.append(&mut timeline.statuses);
+        if let Some(next) = timeline.next {
+            pages.push(format!("{}: --max-id {}", prefix, next));
+        }
+    });
+
+    statuses.sort_by_key(|status| std::cmp::Reverse(status.created_at));
+
+    for status in statuses.iter() {
+        println!("[{}] {} (@{}) at {} (Id={})", status.platform, status.author.name, status.author.username, status.created_at.format("%Y-%m-%d %H:%M:%S %z"), status.id);
+        println!("{}", status.text);
+        for media in status.media.iter() {
+            println!("Media: {}", media);
+        }
+        println!();
+    }
+
+    if !pages.is_empty() {
+        println!(">>>Older posts:");
+        for page in pages.iter() {
+            println!("{}", page);
+        }
+    }
+}
+
+fn command_timeline(config: Config, page: cli::Page) -> io::Result<()> {
+    let mut runtime = runtime();
+
+    let page = page.into();
+    let api = runtime.block_on(create_api(config))?;
+    let result = runtime.block_on(api.home_timeline(&page));
+    handle_timeline_result(result);
+    Ok(())
+}
+
+fn command_mentions(config: Config, page: cli::Page) -> io::Result<()> {
+    let mut runtime = runtime();
+
+    let page = page.into();
+    let api = runtime.block_on(create_api(config))?;
+    let result = runtime.block_on(api.mentions(&page));
+    handle_timeline_result(result);
+    Ok(())
+}
+
+enum PostAction {
+    Favourite,
+    Unfavourite,
+    Bookmark,
+    Unbookmark,
+}
+
+fn command_post_action(config: Config, post: cli::PostRef, action: PostAction) -> io::Result<()> {
+    let mut runtime = runtime();
+
+    let post: fie::data::PostRef = post.into();
+    let mut config = config;
+    //Only single social media is needed
+    config.platforms = fie::config::Platforms {
+        twitter: post.platform == fie::data::Platform::Twitter,
+        gab: post.platform == fie::data::Platform::Gab,
+        mastodon: post.platform == fie::data::Platform::Mastodon,
+        minds: post.platform == fie::data::Platform::Minds,
+    };
+
+    let api = runtime.block_on(create_api(config))?;
+    let result = match action {
+        PostAction::Favourite => runtime.block_on(api.favourite(&post)),
+        PostAction::Unfavourite => runtime.block_on(api.unfavourite(&post)),
+        PostAction::Bookmark => runtime.block_on(api.bookmark(&post)),
+        PostAction::Unbookmark => runtime.block_on(api.unbookmark(&post)),
+    };
+
+    result.map_err(io::Error::other)?;
+    println!("{}(Id={}): done", post.platform, post.id);
+    Ok(())
+}
+
 fn command_batch(config: Config, batch: cli::Batch) -> io::Result<()> {
     let mut runtime = runtime();
 
@@ -123,24 +298,91 @@ fn command_batch(config: Config, batch: cli::Batch) -> io::Result<()> {
         }
     }
 
+    store_upload_cache(&api);
     Ok(())
 }
 
+fn command_store_credentials(credentials: config::Credentials, output: cli::AuthOutput) -> io::Result<()> {
+    let path = match output.print {
+        true => None,
+        false => Config::path().ok(),
+    };
+
+    let path = match path {
+        Some(path) => path,
+        None => {
+            println!("Add following to your fie configuration file:\n{}", credentials);
+            if !credentials.removed.is_empty() {
+                println!("Remove following from it, as it is no longer needed: {}", credentials.removed.join(", "));
+            }
+            return Ok(());
+        }
+    };
+
+    println!("Following will be written into {}:\n{}", path.display(), credentials);
+    if !credentials.removed.is_empty() {
+        println!("Following will be removed, as it is no longer needed: {}", credentials.removed.join(", "));
+    }
+    if !auth::confirm("Proceed? [y/N]: ") {
+        println!("Configuration file is not changed. Add above to it manually or run again.");
+        return Ok(());
+    }
+
+    let backup = config::store_credentials(&path, &credentials)?;
+    println!("Configuration is updated. Previous version is saved as {}", backup.display());
+    Ok(())
+}
+
+fn command_secrets(cmd: cli::Secrets) -> io::Result<()> {
+    let path = config::secrets_path()?;
+
+    match cmd {
+        cli::Secrets::Set { name } => secrets::set(&path, name),
+        cli::Secrets::Get { name } => secrets::get(&path, &name),
+        cli::Secrets::Rotate => secrets::rotate(&path),
+    }
+}
+
 fn run() -> io::Result<()> {
+    let args = cli::Args::new();
+
+    //Configuration may refer to secrets, so they are managed without loading it.
+    let args = match args.cmd {
+        cli::Command::Secrets(cmd) => return command_secrets(cmd),
+        _ => args,
+    };
+
     let mut config = Config::load()?;
     use_twitter_builtin_consumer(&mut config.api.twitter);
-
-    let args = cli::Args::new(&mut config.platforms);
+    args.apply_flags(&mut config.platforms);
 
     match args.cmd {
         cli::Command::Post(post) => command_post(config, post)?,
+        cli::Command::Dm(dm) => command_dm(config, dm)?,
+        cli::Command::Edit(edit) => command_edit(config, edit)?,
+        cli::Command::Whoami => command_whoami(config)?,
+        cli::Command::Timeline(page) => command_timeline(config, page)?,
+        cli::Command::Mentions(page) => command_mentions(config, page)?,
+        cli::Command::Favourite(post) => command_post_action(config, post, PostAction::Favourite)?,
+        cli::Command::Unfavourite(post) => command_post_action(config, post, PostAction::Unfavourite)?,
+        cli::Command::Bookmark(post) => command_post_action(config, post, PostAction::Bookmark)?,
+        cli::Command::Unbookmark(post) => command_post_action(config, post, PostAction::Unbookmark)?,
         cli::Command::Batch(batch) => command_batch(config, batch)?,
+        cli::Command::Secrets(_) => unreachable!(),
         cli::Command::Env(env) => match env {
             cli::Env::Config => println!("{}", Config::path()?.display())
         },
-        cli::Command::Auth(typ) => match typ {
-            cli::Auth::Twitter => {
-                runtime().block_on(auth::twitter(config.api.twitter));
+        cli::Command::Auth(typ) => {
+            let (credentials, output) = match typ {
+                cli::Auth::Twitter { loopback: Some(port), v2: true, output } => (runtime().block_on(auth::twitter_v2(config.api.twitter, port, config::twitter_token_path())), output),
+                cli::Auth::Twitter { loopback, output, .. } => (runtime().block_on(auth::twitter(config.api.twitter, loopback)), output),
+                cli::Auth::Mastodon { host, output } => (runtime().block_on(auth::mastodon(host)), output),
+                cli::Auth::Gab { output } => (runtime().block_on(auth::gab()), output),
+                cli::Auth::Minds { output } => (runtime().block_on(auth::minds(config::minds_token_path())), output),
+            };
+
+            if let Some(credentials) = credentials {
+                command_store_credentials(credentials, output)?;
             }
         }
     }
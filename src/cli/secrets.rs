@@ -0,0 +1,94 @@
+use fie::secrets::Secrets;
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+///Environment variable with passphrase, to avoid prompt.
+pub const PASSPHRASE_ENV: &str = "FIE_SECRETS_PASSPHRASE";
+///Environment variable with new passphrase for rotation, to avoid prompt.
+pub const NEW_PASSPHRASE_ENV: &str = "FIE_SECRETS_NEW_PASSPHRASE";
+
+fn read_passphrase(prompt: &str) -> io::Result<String> {
+    rpassword::read_password_from_tty(Some(prompt)).map_err(|error| io::Error::other(format!("Failed to read passphrase: {}", error)))
+}
+
+///Returns passphrase of existing secrets.
+pub fn passphrase() -> io::Result<String> {
+    match env::var(PASSPHRASE_ENV) {
+        Ok(passphrase) => Ok(passphrase),
+        Err(_) => read_passphrase("Secrets passphrase: "),
+    }
+}
+
+///Returns new passphrase from `env_name` or asks for it twice, to avoid typos.
+fn new_passphrase(env_name: &str) -> io::Result<String> {
+    if let Ok(passphrase) = env::var(env_name) {
+        return match passphrase.is_empty() {
+            true => Err(io::Error::other(format!("{} cannot be empty", env_name))),
+            false => Ok(passphrase),
+        };
+    }
+
+    let passphrase = read_passphrase("New secrets passphrase: ")?;
+    if passphrase.is_empty() {
+        return Err(io::Error::other("Passphrase cannot be empty"));
+    }
+
+    match read_passphrase("Repeat passphrase: ")? == passphrase {
+        true => Ok(passphrase),
+        false => Err(io::Error::other("Passphrases do not match")),
+    }
+}
+
+///Loads and decrypts secrets.
+pub fn load(path: &Path, passphrase: &str) -> io::Result<Secrets> {
+    let data = fs::read(path).map_err(|error| io::Error::other(format!("{}: {}", path.display(), error)))?;
+    Secrets::open(&data, passphrase).map_err(|error| io::Error::other(format!("{}: {}", path.display(), error)))
+}
+
+///Seals secrets, replacing file only once new content is fully written.
+fn save(path: &Path, secrets: &Secrets, passphrase: &str) -> io::Result<()> {
+    let data = secrets.seal(passphrase).map_err(io::Error::other)?;
+    crate::config::replace_private(path, &data)
+}
+
+///Sets secret, creating secrets file if it doesn't exist yet.
+pub fn set(path: &Path, name: String) -> io::Result<()> {
+    let (mut secrets, passphrase) = match path.exists() {
+        true => {
+            let passphrase = passphrase()?;
+            (load(path, &passphrase)?, passphrase)
+        },
+        false => {
+            println!("Creating {}", path.display());
+            (Secrets::new(), new_passphrase(PASSPHRASE_ENV)?)
+        },
+    };
+
+    let value = read_passphrase(&format!("Value of '{}': ", name))?;
+    secrets.set(name, value);
+    save(path, &secrets, &passphrase)
+}
+
+///Prints value of secret.
+pub fn get(path: &Path, name: &str) -> io::Result<()> {
+    let secrets = load(path, &passphrase()?)?;
+
+    match secrets.get(name) {
+        Some(value) => {
+            println!("{}", value);
+            Ok(())
+        },
+        None => Err(io::Error::other(format!("Secret '{}' is not found", name))),
+    }
+}
+
+///Re-encrypts secrets with new passphrase.
+pub fn rotate(path: &Path) -> io::Result<()> {
+    let secrets = load(path, &passphrase()?)?;
+    save(path, &secrets, &new_passphrase(NEW_PASSPHRASE_ENV)?)?;
+    println!("Passphrase is changed");
+    Ok(())
+}
@@ -0,0 +1,93 @@
+//!Cache of uploaded media
+
+use serde_derive::{Serialize, Deserialize};
+
+use crate::data::Platform;
+
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+///Media is considered expired a bit earlier than social media actually forgets it.
+const EXPIRATION_MARGIN: Duration = Duration::from_secs(60 * 60);
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|now| now.as_secs()).unwrap_or(0)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct CachedMedia {
+    id: String,
+    ///Unix timestamp of upload.
+    uploaded: u64,
+}
+
+///Cache of uploaded media, keyed by SHA-256 of uploaded content.
+///
+///Only platforms, that allow to attach the same media to multiple posts, are cached.
+///Currently it is only Twitter, see `Platform::media_lifetime`.
+///
+///Cache can be serialized to be re-used later, as it keeps track of media expiration.
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct UploadCache {
+    #[serde(default)]
+    twitter: HashMap<String, CachedMedia>,
+}
+
+impl UploadCache {
+    ///Creates empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Calculates key of content.
+    pub fn digest(data: &[u8]) -> String {
+        let digest = ring::digest::digest(&ring::digest::SHA256, data);
+        data_encoding::HEXLOWER.encode(digest.as_ref())
+    }
+
+    fn platform(&mut self, platform: Platform) -> Option<&mut HashMap<String, CachedMedia>> {
+        match platform {
+            Platform::Twitter => Some(&mut self.twitter),
+            Platform::Gab | Platform::Mastodon | Platform::Minds => None,
+        }
+    }
+
+    fn is_valid(platform: Platform, media: &CachedMedia, now: u64) -> bool {
+        match platform.media_lifetime() {
+            Some(lifetime) => media.uploaded + lifetime.as_secs() > now + EXPIRATION_MARGIN.as_secs(),
+            None => false,
+        }
+    }
+
+    ///Retrieves ID of media with `digest`, if it is still valid.
+    pub fn get(&mut self, platform: Platform, digest: &str) -> Option<&str> {
+        let now = now();
+        let media = self.platform(platform)?;
+
+        match media.get(digest) {
+            Some(cached) if Self::is_valid(platform, cached, now) => (),
+            Some(_) => {
+                media.remove(digest);
+                return None;
+            },
+            None => return None,
+        }
+
+        media.get(digest).map(|cached| cached.id.as_str())
+    }
+
+    ///Stores ID of newly uploaded media with `digest`.
+    ///
+    ///Does nothing if platform doesn't allow to re-use media.
+    pub fn insert(&mut self, platform: Platform, digest: String, id: String) {
+        if let Some(media) = self.platform(platform) {
+            media.insert(digest, CachedMedia { id, uploaded: now() });
+        }
+    }
+
+    ///Removes expired media.
+    pub fn purge(&mut self) {
+        let now = now();
+        self.twitter.retain(|_, cached| Self::is_valid(Platform::Twitter, cached, now));
+    }
+}
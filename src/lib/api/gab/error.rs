@@ -13,6 +13,10 @@ pub enum GabError {
     ///
     ///Should contain `id`
     ImageUploadInvalidResponse,
+    ///Failed to send request to delete uploaded media.
+    MediaDeleteSendError,
+    ///Server rejected deletion of uploaded media.
+    MediaDeleteServerReject,
     ///Failed to send request to perform text post.
     PostUploadSendError,
     ///Server rejected posting.
@@ -21,6 +25,42 @@ pub enum GabError {
     ///
     ///Should contain `id`
     PostUploadInvalidResponse,
+    ///Failed to send request to edit post.
+    PostEditSendError,
+    ///Server rejected post's edit.
+    PostEditServerReject,
+    ///Server responded with invalid data
+    ///
+    ///Should contain `id`
+    PostEditInvalidResponse,
+    ///Failed to send request to send direct message.
+    DirectMessageSendError,
+    ///Server rejected direct message.
+    DirectMessageServerReject,
+    ///Server responded with invalid data
+    ///
+    ///Should contain `id`
+    DirectMessageInvalidResponse,
+    ///Failed to send request to verify credentials.
+    VerifyCredentialsSendError,
+    ///Server rejected credentials.
+    VerifyCredentialsServerReject,
+    ///Server responded with invalid data.
+    ///
+    ///Should contain account's information
+    VerifyCredentialsInvalidResponse,
+    ///Failed to send request to fetch timeline.
+    TimelineSendError,
+    ///Server rejected timeline request.
+    TimelineServerReject,
+    ///Server responded with invalid data.
+    ///
+    ///Should contain list of posts
+    TimelineInvalidResponse,
+    ///Failed to send request to favourite or unfavourite post.
+    FavouriteSendError,
+    ///Server rejected to favourite or unfavourite post.
+    FavouriteServerReject,
 
 }
 
@@ -36,9 +76,25 @@ impl Error for GabError {
             &GabError::ImageUploadSendError => "Failed to send request to upload image",
             &GabError::ImageUploadServerReject => "Server rejected upload of image",
             &GabError::ImageUploadInvalidResponse => "Server sent invalid response. Doesn't contain field id",
+            &GabError::MediaDeleteSendError => "Failed to send request to delete uploaded media",
+            &GabError::MediaDeleteServerReject => "Server rejected deletion of uploaded media",
             &GabError::PostUploadSendError => "Failed to send request to perform text post",
             &GabError::PostUploadServerReject => "Server rejected posting",
             &GabError::PostUploadInvalidResponse => "Server sent invalid response. Doesn't contain field id",
+            &GabError::PostEditSendError => "Failed to send request to edit post",
+            &GabError::PostEditServerReject => "Server rejected edit of post",
+            &GabError::PostEditInvalidResponse => "Server sent invalid response. Doesn't contain field id",
+            &GabError::DirectMessageSendError => "Failed to send request to send direct message",
+            &GabError::DirectMessageServerReject => "Server rejected direct message",
+            &GabError::DirectMessageInvalidResponse => "Server sent invalid response. Doesn't contain field id",
+            &GabError::VerifyCredentialsSendError => "Failed to send request to verify credentials",
+            &GabError::VerifyCredentialsServerReject => "Server rejected credentials",
+            &GabError::VerifyCredentialsInvalidResponse => "Server sent invalid response. Doesn't contain account information",
+            &GabError::TimelineSendError => "Failed to send request to fetch timeline",
+            &GabError::TimelineServerReject => "Server rejected timeline request",
+            &GabError::TimelineInvalidResponse => "Server sent invalid response. Doesn't contain list of posts",
+            &GabError::FavouriteSendError => "Failed to send request to favourite post",
+            &GabError::FavouriteServerReject => "Server rejected to favourite post",
         }
     }
 }
@@ -3,10 +3,13 @@
 pub mod data;
 mod error;
 
-const IMAGES_URL: &'static str = "https://gab.com/api/v1/media";
-const POST_URL: &'static str = "https://gab.com/api/v1/statuses";
+const IMAGES_URL: &str = "https://gab.com/api/v1/media";
+const POST_URL: &str = "https://gab.com/api/v1/statuses";
+const HOME_TIMELINE_URL: &str = "https://gab.com/api/v1/timelines/home";
+const NOTIFICATIONS_URL: &str = "https://gab.com/api/v1/notifications";
+const VERIFY_CREDENTIALS_URL: &str = "https://gab.com/api/v1/accounts/verify_credentials";
 
-use crate::data::PostFlags;
+use crate::data::{Focus, Page, Platform, PostFlags, PostId, Timeline};
 use data::*;
 pub use error::GabError;
 
@@ -28,10 +31,15 @@ impl Gab {
     ///Prepares image upload request.
     ///
     ///Result contains `id` from `EntityId`
-    pub async fn upload_image(&self, name: &str, mime: &Mime, data: &[u8]) -> Result<String, GabError> {
+    ///
+    ///`focus` sets focal point of image.
+    pub async fn upload_image(&self, name: &str, mime: &Mime, data: &[u8], focus: Option<&Focus>) -> Result<String, GabError> {
         let mut form = multipart::Form::new();
 
         form.add_file_field("file".to_string(), name.to_string(), mime, data);
+        if let Some(focus) = focus {
+            form.add_field("focus".to_string(), focus.to_string().as_bytes());
+        }
 
         let req = Request::post(IMAGES_URL).expect("To create request").bearer_auth(self.token.as_str()).multipart(form).global().send();
 
@@ -55,12 +63,34 @@ impl Gab {
         }
     }
 
+    ///Prepares request to delete uploaded media.
+    ///
+    ///Used to clean up media, that is not going to be attached to post.
+    pub async fn delete_media(&self, id: &str) -> Result<(), GabError> {
+        let url = format!("{}/{}", IMAGES_URL, id);
+        let req = Request::delete(url).expect("To create request")
+                                      .bearer_auth(self.token.as_str())
+                                      .empty()
+                                      .global()
+                                      .send();
+
+        let resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(GabError::MediaDeleteSendError),
+        }.map_err(|_| GabError::MediaDeleteSendError)?;
+
+        match resp.is_success() {
+            true => Ok(()),
+            false => Err(GabError::MediaDeleteServerReject),
+        }
+    }
+
     ///Prepares post upload request.
     pub async fn post(&self, message: &str, media_attachments: &[String], flags: &PostFlags) -> Result<crate::data::PostId, GabError> {
         let req = Request::post(POST_URL).expect("To create request")
                                          .bearer_auth(self.token.as_str())
                                          .json(&NewStatus::new(&message, &media_attachments, &flags))
-                                         .expect("To serialzie post data")
+                                         .expect("To serialize post data")
                                          .global()
                                          .send();
         let mut resp = match matsu!(req) {
@@ -81,4 +111,206 @@ impl Gab {
             Err(_) => Err(GabError::PostUploadInvalidResponse),
         }
     }
+
+    ///Prepares direct message request.
+    ///
+    ///Message is posted as status, visible only to mentioned recipient.
+    pub async fn send_direct(&self, recipient: &str, message: &str, media_attachments: &[String], flags: &PostFlags) -> Result<crate::data::PostId, GabError> {
+        let message = direct_message(recipient, message);
+        let req = Request::post(POST_URL).expect("To create request")
+                                         .bearer_auth(self.token.as_str())
+                                         .json(&NewStatus::new(&message, media_attachments, flags).direct())
+                                         .expect("To serialize post data")
+                                         .global()
+                                         .send();
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(err) => match matsu!(matsu!(err)) {
+                Ok(resp) => resp,
+                Err(_) => return Err(GabError::DirectMessageSendError)
+            }
+        }.map_err(|_| GabError::DirectMessageSendError)?;
+
+
+        if !resp.is_success() {
+            return Err(GabError::DirectMessageServerReject)
+        }
+
+        match matsu!(resp.json::<EntityId>()) {
+            Ok(data) => Ok(data.id.into()),
+            Err(_) => Err(GabError::DirectMessageInvalidResponse),
+        }
+    }
+
+    ///Prepares post edit request.
+    ///
+    ///Replaces text, attachments and sensitive flag of existing status.
+    pub async fn edit(&self, id: &PostId, message: &str, media_attachments: &[String], flags: &PostFlags) -> Result<PostId, GabError> {
+        let url = format!("{}/{}", POST_URL, id);
+        let req = Request::put(url).expect("To create request")
+                                   .bearer_auth(self.token.as_str())
+                                   .json(&NewStatus::new(message, media_attachments, flags))
+                                   .expect("To serialize post data")
+                                   .global()
+                                   .send();
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(err) => match matsu!(matsu!(err)) {
+                Ok(resp) => resp,
+                Err(_) => return Err(GabError::PostEditSendError)
+            }
+        }.map_err(|_| GabError::PostEditSendError)?;
+
+
+        if !resp.is_success() {
+            return Err(GabError::PostEditServerReject)
+        }
+
+        match matsu!(resp.json::<EntityId>()) {
+            Ok(data) => Ok(data.id.into()),
+            Err(_) => Err(GabError::PostEditInvalidResponse),
+        }
+    }
+
+    ///Prepares credentials verification request.
+    pub async fn verify_credentials(&self) -> Result<crate::data::Account, GabError> {
+        let req = Request::get(VERIFY_CREDENTIALS_URL).expect("To create request")
+                                                      .bearer_auth(self.token.as_str())
+                                                      .empty()
+                                                      .global()
+                                                      .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(GabError::VerifyCredentialsSendError),
+        }.map_err(|_| GabError::VerifyCredentialsSendError)?;
+
+        if !resp.is_success() {
+            return Err(GabError::VerifyCredentialsServerReject)
+        }
+
+        match matsu!(resp.json::<Account>()) {
+            Ok(account) => Ok(account.into()),
+            Err(_) => Err(GabError::VerifyCredentialsInvalidResponse),
+        }
+    }
+
+    ///Prepares request to fetch home timeline.
+    pub async fn home_timeline(&self, page: &Page) -> Result<Timeline, GabError> {
+        let url = HOME_TIMELINE_URL;
+        let req = Request::get(url).expect("To create request")
+                                   .bearer_auth(self.token.as_str())
+                                   .query(&TimelineQuery::new(page))
+                                   .empty()
+                                   .global()
+                                   .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(GabError::TimelineSendError),
+        }.map_err(|_| GabError::TimelineSendError)?;
+
+        if !resp.is_success() {
+            return Err(GabError::TimelineServerReject)
+        }
+
+        let statuses = match matsu!(resp.json::<Vec<Status>>()) {
+            Ok(statuses) => statuses,
+            Err(_) => return Err(GabError::TimelineInvalidResponse),
+        };
+
+        //max_id is exclusive so oldest status is where next page starts.
+        let next = statuses.last().map(|status| status.id.clone());
+        let mut result = Vec::with_capacity(statuses.len());
+        for status in statuses {
+            match status.into_status(Platform::Gab) {
+                Some(status) => result.push(status),
+                None => return Err(GabError::TimelineInvalidResponse),
+            }
+        }
+
+        Ok(Timeline {
+            statuses: result,
+            next,
+        })
+    }
+
+    ///Prepares request to fetch statuses that mention user.
+    ///
+    ///Statuses are taken from notifications, hence paging uses notification's IDs.
+    pub async fn mentions(&self, page: &Page) -> Result<Timeline, GabError> {
+        let url = NOTIFICATIONS_URL;
+        let mut query = TimelineQuery::new(page);
+        query.types = Some("mention");
+
+        let req = Request::get(url).expect("To create request")
+                                   .bearer_auth(self.token.as_str())
+                                   .query(&query)
+                                   .empty()
+                                   .global()
+                                   .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(GabError::TimelineSendError),
+        }.map_err(|_| GabError::TimelineSendError)?;
+
+        if !resp.is_success() {
+            return Err(GabError::TimelineServerReject)
+        }
+
+        let notifications = match matsu!(resp.json::<Vec<Notification>>()) {
+            Ok(notifications) => notifications,
+            Err(_) => return Err(GabError::TimelineInvalidResponse),
+        };
+
+        let next = notifications.last().map(|notification| notification.id.clone());
+        let mut result = Vec::with_capacity(notifications.len());
+        //Older servers ignore filter by type
+        for notification in notifications.into_iter().filter(|notification| notification.typ == "mention") {
+            let status = match notification.status {
+                Some(status) => status,
+                None => continue,
+            };
+
+            match status.into_status(Platform::Gab) {
+                Some(status) => result.push(status),
+                None => return Err(GabError::TimelineInvalidResponse),
+            }
+        }
+
+        Ok(Timeline {
+            statuses: result,
+            next,
+        })
+    }
+
+    async fn status_action(&self, id: &PostId, action: &str, send_error: GabError, reject_error: GabError) -> Result<(), GabError> {
+        let url = format!("{}/{}/{}", POST_URL, id, action);
+        let req = Request::post(url).expect("To create request")
+                                    .bearer_auth(self.token.as_str())
+                                    .empty()
+                                    .global()
+                                    .send();
+
+        let resp = match matsu!(req) {
+            Ok(Ok(resp)) => resp,
+            _ => return Err(send_error),
+        };
+
+        match resp.is_success() {
+            true => Ok(()),
+            false => Err(reject_error),
+        }
+    }
+
+    ///Prepares request to favourite status.
+    pub async fn favourite(&self, id: &PostId) -> Result<(), GabError> {
+        matsu!(self.status_action(id, "favourite", GabError::FavouriteSendError, GabError::FavouriteServerReject))
+    }
+
+    ///Prepares request to remove status from favourites.
+    pub async fn unfavourite(&self, id: &PostId) -> Result<(), GabError> {
+        matsu!(self.status_action(id, "unfavourite", GabError::FavouriteSendError, GabError::FavouriteServerReject))
+    }
 }
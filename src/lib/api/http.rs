@@ -15,6 +15,7 @@ use core::time::Duration;
 pub use core::future::Future;
 
 static mut TIMEOUT: u64 = 5;
+static mut MEDIA_TIMEOUT: u64 = 120;
 
 ///Yukikaze config
 pub struct Conf;
@@ -36,14 +37,27 @@ mod gen {
 
 pub use gen::GlobalRequest;
 
-///Sets current timeout value;
+///Sets current timeout values;
 pub fn set_timeout(settings: &Settings) {
     unsafe {
         TIMEOUT = settings.timeout;
+        MEDIA_TIMEOUT = settings.media_timeout;
     }
 }
 
+///Waits for specified duration.
+pub async fn delay(duration: Duration) {
+    use yukikaze::async_timer::Oneshot;
+
+    matsu!(<Conf as Config>::Timer::new(duration))
+}
+
 ///Gets currently set timeout value
 pub fn get_timeout() -> Duration {
     unsafe { Duration::from_secs(TIMEOUT) }
 }
+
+///Gets currently set timeout value for processing of uploaded media
+pub fn get_media_timeout() -> Duration {
+    unsafe { Duration::from_secs(MEDIA_TIMEOUT) }
+}
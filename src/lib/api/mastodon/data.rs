@@ -2,7 +2,7 @@
 
 use serde_derive::{Serialize, Deserialize};
 
-use crate::data::PostFlags;
+use crate::data::{AttachmentKind, Page, Platform, PostFlags};
 
 ///Generic payload for response that contains entity's information.
 ///
@@ -13,6 +13,43 @@ pub struct EntityId {
     pub id: String
 }
 
+///Uploaded media
+#[derive(Deserialize, Debug)]
+pub struct MediaAttachment {
+    ///Identifier
+    pub id: String,
+    ///Kind of media
+    #[serde(rename = "type")]
+    pub kind: AttachmentKind,
+    ///URL of processed media.
+    ///
+    ///Missing while media is being processed.
+    pub url: Option<String>,
+}
+
+///User's account information.
+#[derive(Deserialize, Debug)]
+pub struct Account {
+    ///Identifier
+    pub id: String,
+    ///Username, without domain
+    pub username: String,
+    ///Username used for mentions, with domain for remote users
+    pub acct: String,
+    ///Display name
+    pub display_name: String,
+}
+
+impl From<Account> for crate::data::Account {
+    fn from(account: Account) -> Self {
+        Self {
+            id: account.id,
+            username: account.acct,
+            name: account.display_name,
+        }
+    }
+}
+
 ///Posts new message on timeline
 #[derive(Serialize, Debug)]
 pub struct NewStatus<'a> {
@@ -20,6 +57,8 @@ pub struct NewStatus<'a> {
     ///List of `EntityId`'s id to attach
     pub media_ids: &'a [String],
     sensitive: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    visibility: Option<&'static str>,
 }
 
 impl<'a> NewStatus<'a> {
@@ -29,6 +68,99 @@ impl<'a> NewStatus<'a> {
             status,
             media_ids,
             sensitive: flags.nsfw,
+            visibility: None,
         }
     }
+
+    ///Makes status visible only to mentioned users.
+    pub fn direct(mut self) -> Self {
+        self.visibility = Some("direct");
+        self
+    }
+}
+
+///Creates text of direct status by mentioning recipient at the beginning.
+pub fn direct_message(recipient: &str, message: &str) -> String {
+    format!("@{} {}", recipient.trim_start_matches('@'), message)
+}
+
+///Query to fetch page of timeline
+#[derive(Serialize, Debug)]
+pub struct TimelineQuery<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    since_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,
+    ///Types of notifications to fetch
+    #[serde(rename = "types[]", skip_serializing_if = "Option::is_none")]
+    pub types: Option<&'static str>,
+}
+
+impl<'a> TimelineQuery<'a> {
+    ///Creates new instance
+    pub fn new(page: &'a Page) -> Self {
+        Self {
+            max_id: page.max_id.as_deref(),
+            since_id: page.since_id.as_deref(),
+            limit: page.limit,
+            types: None,
+        }
+    }
+}
+
+///Media attachment
+#[derive(Deserialize, Debug)]
+pub struct Attachment {
+    ///Identifier
+    pub id: String,
+    ///URL of original media
+    pub url: String,
+}
+
+///Status from timeline
+#[derive(Deserialize, Debug)]
+pub struct Status {
+    ///Identifier
+    pub id: String,
+    ///Time of creation in RFC3339 format
+    pub created_at: String,
+    ///HTML content
+    pub content: String,
+    ///Author
+    pub account: Account,
+    ///Attachments
+    #[serde(default)]
+    pub media_attachments: Vec<Attachment>,
+}
+
+impl Status {
+    ///Converts into generic status.
+    ///
+    ///Returns `None` if creation time is invalid.
+    pub fn into_status(self, platform: Platform) -> Option<crate::data::Status> {
+        let created_at = time::OffsetDateTime::parse(&self.created_at, time::Format::Rfc3339).ok()?;
+
+        Some(crate::data::Status {
+            platform,
+            id: self.id.into(),
+            author: self.account.into(),
+            text: crate::data::html_to_text(&self.content),
+            created_at,
+            media: self.media_attachments.into_iter().map(|media| media.url).collect(),
+        })
+    }
+}
+
+///Notification
+#[derive(Deserialize, Debug)]
+pub struct Notification {
+    ///Identifier, used for paging
+    pub id: String,
+    ///Type of notification
+    #[serde(rename = "type")]
+    pub typ: String,
+    ///Status, if notification is about it
+    pub status: Option<Status>,
 }
@@ -21,6 +21,14 @@ pub enum MastodonError {
     ///
     ///Should contain `id`
     ImageUploadInvalidResponse,
+    ///Failed to send request to delete uploaded media.
+    MediaDeleteSendError,
+    ///Server rejected deletion of uploaded media.
+    MediaDeleteServerReject,
+    ///Server failed to process uploaded media.
+    MediaProcessingFailed,
+    ///Server didn't finish processing of uploaded media in time.
+    MediaProcessingTimeout,
     ///Failed to send request to perform text post.
     PostUploadSendError,
     ///Server rejected posting.
@@ -29,6 +37,46 @@ pub enum MastodonError {
     ///
     ///Should contain `id`
     PostUploadInvalidResponse,
+    ///Failed to send request to edit post.
+    PostEditSendError,
+    ///Server rejected post's edit.
+    PostEditServerReject,
+    ///Server responded with invalid data
+    ///
+    ///Should contain `id`
+    PostEditInvalidResponse,
+    ///Failed to send request to send direct message.
+    DirectMessageSendError,
+    ///Server rejected direct message.
+    DirectMessageServerReject,
+    ///Server responded with invalid data
+    ///
+    ///Should contain `id`
+    DirectMessageInvalidResponse,
+    ///Failed to send request to verify credentials.
+    VerifyCredentialsSendError,
+    ///Server rejected credentials.
+    VerifyCredentialsServerReject,
+    ///Server responded with invalid data.
+    ///
+    ///Should contain account's information
+    VerifyCredentialsInvalidResponse,
+    ///Failed to send request to fetch timeline.
+    TimelineSendError,
+    ///Server rejected timeline request.
+    TimelineServerReject,
+    ///Server responded with invalid data.
+    ///
+    ///Should contain list of posts
+    TimelineInvalidResponse,
+    ///Failed to send request to favourite or unfavourite post.
+    FavouriteSendError,
+    ///Server rejected to favourite or unfavourite post.
+    FavouriteServerReject,
+    ///Failed to send request to bookmark or unbookmark post.
+    BookmarkSendError,
+    ///Server rejected to bookmark or unbookmark post.
+    BookmarkServerReject,
 
 }
 
@@ -46,9 +94,29 @@ impl Error for MastodonError {
             &MastodonError::ImageUploadSendError => "Failed to send request to upload image",
             &MastodonError::ImageUploadServerReject => "Server rejected upload of image",
             &MastodonError::ImageUploadInvalidResponse => "Server sent invalid response. Doesn't contain field id",
+            &MastodonError::MediaDeleteSendError => "Failed to send request to delete uploaded media",
+            &MastodonError::MediaDeleteServerReject => "Server rejected deletion of uploaded media",
+            &MastodonError::MediaProcessingFailed => "Server failed to process uploaded media",
+            &MastodonError::MediaProcessingTimeout => "Server didn't finish processing of uploaded media in time",
             &MastodonError::PostUploadSendError => "Failed to send request to perform text post",
             &MastodonError::PostUploadServerReject => "Server rejected posting",
             &MastodonError::PostUploadInvalidResponse => "Server sent invalid response. Doesn't contain field id",
+            &MastodonError::PostEditSendError => "Failed to send request to edit post",
+            &MastodonError::PostEditServerReject => "Server rejected edit of post",
+            &MastodonError::PostEditInvalidResponse => "Server sent invalid response. Doesn't contain field id",
+            &MastodonError::DirectMessageSendError => "Failed to send request to send direct message",
+            &MastodonError::DirectMessageServerReject => "Server rejected direct message",
+            &MastodonError::DirectMessageInvalidResponse => "Server sent invalid response. Doesn't contain field id",
+            &MastodonError::VerifyCredentialsSendError => "Failed to send request to verify credentials",
+            &MastodonError::VerifyCredentialsServerReject => "Server rejected credentials",
+            &MastodonError::VerifyCredentialsInvalidResponse => "Server sent invalid response. Doesn't contain account information",
+            &MastodonError::TimelineSendError => "Failed to send request to fetch timeline",
+            &MastodonError::TimelineServerReject => "Server rejected timeline request",
+            &MastodonError::TimelineInvalidResponse => "Server sent invalid response. Doesn't contain list of posts",
+            &MastodonError::FavouriteSendError => "Failed to send request to favourite post",
+            &MastodonError::FavouriteServerReject => "Server rejected to favourite post",
+            &MastodonError::BookmarkSendError => "Failed to send request to bookmark post",
+            &MastodonError::BookmarkServerReject => "Server rejected to bookmark post",
         }
     }
 }
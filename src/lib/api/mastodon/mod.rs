@@ -1,7 +1,9 @@
 //!Mastodon API
 
-use super::http::{multipart, Uri, GlobalRequest, Mime, Request, matsu};
-use crate::data::PostFlags;
+use super::http::{self, multipart, Uri, GlobalRequest, Mime, Request, matsu};
+use crate::data::{Focus, Page, Platform, PostFlags, PostId, Timeline};
+
+use core::time::Duration;
 
 pub mod data;
 mod error;
@@ -32,14 +34,21 @@ impl Mastodon {
         })
     }
 
-    ///Prepares image upload request.
+    ///Prepares media upload request.
+    ///
+    ///Accepts images, video and audio.
+    ///If media is processed asynchronously, waits until it is ready to be attached.
+    ///`focus` sets focal point of image.
     ///
-    ///Result contains `id` from `EntityId`
-    pub async fn upload_image(&self, name: &str, mime: &Mime, data: &[u8]) -> Result<String, MastodonError> {
-        let url = format!("https://{}/api/v1/media", &self.config.host);
+    ///Result contains `id` from `MediaAttachment`
+    pub async fn upload_image(&self, name: &str, mime: &Mime, data: &[u8], focus: Option<&Focus>) -> Result<String, MastodonError> {
+        let url = format!("https://{}/api/v2/media", &self.config.host);
         let mut form = multipart::Form::new();
 
         form.add_file_field("file".to_string(), name.to_string(), mime, data);
+        if let Some(focus) = focus {
+            form.add_field("focus".to_string(), focus.to_string().as_bytes());
+        }
 
         let req = Request::post(url).expect("To create request").bearer_auth(self.config.access_token.as_str()).multipart(form).global().send();
 
@@ -57,11 +66,73 @@ impl Mastodon {
             return Err(MastodonError::ImageUploadServerReject)
         }
 
-        match matsu!(resp.json::<data::EntityId>()) {
-            Ok(data) => Ok(data.id),
-            Err(_) => Err(MastodonError::PostUploadInvalidResponse),
+        let media = match matsu!(resp.json::<data::MediaAttachment>()) {
+            Ok(media) => media,
+            Err(_) => return Err(MastodonError::ImageUploadInvalidResponse),
+        };
+
+        match media.url {
+            Some(_) => Ok(media.id),
+            None => matsu!(self.wait_media(media.id)),
         }
+    }
+
+    ///Waits until uploaded media is processed.
+    ///
+    ///Gives up after `Settings::media_timeout`.
+    async fn wait_media(&self, id: String) -> Result<String, MastodonError> {
+        const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+        let url = format!("https://{}/api/v1/media/{}", &self.config.host, id);
+        let max_wait = http::get_media_timeout();
+        let mut waited = Duration::from_secs(0);
+
+        while waited < max_wait {
+            matsu!(http::delay(POLL_INTERVAL));
+            waited += POLL_INTERVAL;
+
+            let req = Request::get(url.as_str()).expect("To create request")
+                                                .bearer_auth(self.config.access_token.as_str())
+                                                .empty()
+                                                .global()
+                                                .send();
 
+            let resp = match matsu!(req) {
+                Ok(resp) => resp,
+                Err(_) => return Err(MastodonError::ImageUploadSendError),
+            }.map_err(|_| MastodonError::ImageUploadSendError)?;
+
+            match resp.status().as_u16() {
+                200 => return Ok(id),
+                //Still processing
+                206 => continue,
+                _ => return Err(MastodonError::MediaProcessingFailed),
+            }
+        }
+
+        Err(MastodonError::MediaProcessingTimeout)
+    }
+
+    ///Prepares request to delete uploaded media.
+    ///
+    ///Used to clean up media, that is not going to be attached to post.
+    pub async fn delete_media(&self, id: &str) -> Result<(), MastodonError> {
+        let url = format!("https://{}/api/v1/media/{}", &self.config.host, id);
+        let req = Request::delete(url).expect("To create request")
+                                      .bearer_auth(self.config.access_token.as_str())
+                                      .empty()
+                                      .global()
+                                      .send();
+
+        let resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(MastodonError::MediaDeleteSendError),
+        }.map_err(|_| MastodonError::MediaDeleteSendError)?;
+
+        match resp.is_success() {
+            true => Ok(()),
+            false => Err(MastodonError::MediaDeleteServerReject),
+        }
     }
 
     ///Prepares post upload request.
@@ -70,7 +141,7 @@ impl Mastodon {
         let req = Request::post(url).expect("To create request")
                                     .bearer_auth(self.config.access_token.as_str())
                                     .json(&data::NewStatus::new(&message, &media_attachments, &flags))
-                                    .expect("To serialzie post data")
+                                    .expect("To serialize post data")
                                     .global()
                                     .send();
 
@@ -92,4 +163,221 @@ impl Mastodon {
             Err(_) => Err(MastodonError::PostUploadInvalidResponse),
         }
     }
+
+    ///Prepares direct message request.
+    ///
+    ///Message is posted as status, visible only to mentioned recipient.
+    pub async fn send_direct(&self, recipient: &str, message: &str, media_attachments: &[String], flags: &PostFlags) -> Result<crate::data::PostId, MastodonError> {
+        let url = format!("https://{}/api/v1/statuses", &self.config.host);
+        let message = data::direct_message(recipient, message);
+        let req = Request::post(url).expect("To create request")
+                                    .bearer_auth(self.config.access_token.as_str())
+                                    .json(&data::NewStatus::new(&message, media_attachments, flags).direct())
+                                    .expect("To serialize post data")
+                                    .global()
+                                    .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(err) => match matsu!(matsu!(err)) {
+                Ok(resp) => resp,
+                Err(_) => return Err(MastodonError::DirectMessageSendError)
+            }
+        }.map_err(|_| MastodonError::DirectMessageSendError)?;
+
+
+        if !resp.is_success() {
+            return Err(MastodonError::DirectMessageServerReject)
+        }
+
+        match matsu!(resp.json::<data::EntityId>()) {
+            Ok(data) => Ok(data.id.into()),
+            Err(_) => Err(MastodonError::DirectMessageInvalidResponse),
+        }
+    }
+
+    ///Prepares post edit request.
+    ///
+    ///Replaces text, attachments and sensitive flag of existing status.
+    ///Requires Mastodon 3.5 or later.
+    pub async fn edit(&self, id: &PostId, message: &str, media_attachments: &[String], flags: &PostFlags) -> Result<PostId, MastodonError> {
+        let url = format!("https://{}/api/v1/statuses/{}", &self.config.host, id);
+        let req = Request::put(url).expect("To create request")
+                                   .bearer_auth(self.config.access_token.as_str())
+                                   .json(&data::NewStatus::new(message, media_attachments, flags))
+                                   .expect("To serialize post data")
+                                   .global()
+                                   .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(err) => match matsu!(matsu!(err)) {
+                Ok(resp) => resp,
+                Err(_) => return Err(MastodonError::PostEditSendError)
+            }
+        }.map_err(|_| MastodonError::PostEditSendError)?;
+
+
+        if !resp.is_success() {
+            return Err(MastodonError::PostEditServerReject)
+        }
+
+        match matsu!(resp.json::<data::EntityId>()) {
+            Ok(data) => Ok(data.id.into()),
+            Err(_) => Err(MastodonError::PostEditInvalidResponse),
+        }
+    }
+
+    ///Prepares credentials verification request.
+    pub async fn verify_credentials(&self) -> Result<crate::data::Account, MastodonError> {
+        let url = format!("https://{}/api/v1/accounts/verify_credentials", &self.config.host);
+        let req = Request::get(url).expect("To create request")
+                                   .bearer_auth(self.config.access_token.as_str())
+                                   .empty()
+                                   .global()
+                                   .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(MastodonError::VerifyCredentialsSendError),
+        }.map_err(|_| MastodonError::VerifyCredentialsSendError)?;
+
+        if !resp.is_success() {
+            return Err(MastodonError::VerifyCredentialsServerReject)
+        }
+
+        match matsu!(resp.json::<data::Account>()) {
+            Ok(account) => Ok(account.into()),
+            Err(_) => Err(MastodonError::VerifyCredentialsInvalidResponse),
+        }
+    }
+
+    ///Prepares request to fetch home timeline.
+    pub async fn home_timeline(&self, page: &Page) -> Result<Timeline, MastodonError> {
+        let url = format!("https://{}/api/v1/timelines/home", &self.config.host);
+        let req = Request::get(url).expect("To create request")
+                                   .bearer_auth(self.config.access_token.as_str())
+                                   .query(&data::TimelineQuery::new(page))
+                                   .empty()
+                                   .global()
+                                   .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(MastodonError::TimelineSendError),
+        }.map_err(|_| MastodonError::TimelineSendError)?;
+
+        if !resp.is_success() {
+            return Err(MastodonError::TimelineServerReject)
+        }
+
+        let statuses = match matsu!(resp.json::<Vec<data::Status>>()) {
+            Ok(statuses) => statuses,
+            Err(_) => return Err(MastodonError::TimelineInvalidResponse),
+        };
+
+        //max_id is exclusive so oldest status is where next page starts.
+        let next = statuses.last().map(|status| status.id.clone());
+        let mut result = Vec::with_capacity(statuses.len());
+        for status in statuses {
+            match status.into_status(Platform::Mastodon) {
+                Some(status) => result.push(status),
+                None => return Err(MastodonError::TimelineInvalidResponse),
+            }
+        }
+
+        Ok(Timeline {
+            statuses: result,
+            next,
+        })
+    }
+
+    ///Prepares request to fetch statuses that mention user.
+    ///
+    ///Statuses are taken from notifications, hence paging uses notification's IDs.
+    pub async fn mentions(&self, page: &Page) -> Result<Timeline, MastodonError> {
+        let url = format!("https://{}/api/v1/notifications", &self.config.host);
+        let mut query = data::TimelineQuery::new(page);
+        query.types = Some("mention");
+
+        let req = Request::get(url).expect("To create request")
+                                   .bearer_auth(self.config.access_token.as_str())
+                                   .query(&query)
+                                   .empty()
+                                   .global()
+                                   .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(MastodonError::TimelineSendError),
+        }.map_err(|_| MastodonError::TimelineSendError)?;
+
+        if !resp.is_success() {
+            return Err(MastodonError::TimelineServerReject)
+        }
+
+        let notifications = match matsu!(resp.json::<Vec<data::Notification>>()) {
+            Ok(notifications) => notifications,
+            Err(_) => return Err(MastodonError::TimelineInvalidResponse),
+        };
+
+        let next = notifications.last().map(|notification| notification.id.clone());
+        let mut result = Vec::with_capacity(notifications.len());
+        //Older servers ignore filter by type
+        for notification in notifications.into_iter().filter(|notification| notification.typ == "mention") {
+            let status = match notification.status {
+                Some(status) => status,
+                None => continue,
+            };
+
+            match status.into_status(Platform::Mastodon) {
+                Some(status) => result.push(status),
+                None => return Err(MastodonError::TimelineInvalidResponse),
+            }
+        }
+
+        Ok(Timeline {
+            statuses: result,
+            next,
+        })
+    }
+
+    async fn status_action(&self, id: &PostId, action: &str, send_error: MastodonError, reject_error: MastodonError) -> Result<(), MastodonError> {
+        let url = format!("https://{}/api/v1/statuses/{}/{}", &self.config.host, id, action);
+        let req = Request::post(url).expect("To create request")
+                                    .bearer_auth(self.config.access_token.as_str())
+                                    .empty()
+                                    .global()
+                                    .send();
+
+        let resp = match matsu!(req) {
+            Ok(Ok(resp)) => resp,
+            _ => return Err(send_error),
+        };
+
+        match resp.is_success() {
+            true => Ok(()),
+            false => Err(reject_error),
+        }
+    }
+
+    ///Prepares request to favourite status.
+    pub async fn favourite(&self, id: &PostId) -> Result<(), MastodonError> {
+        matsu!(self.status_action(id, "favourite", MastodonError::FavouriteSendError, MastodonError::FavouriteServerReject))
+    }
+
+    ///Prepares request to remove status from favourites.
+    pub async fn unfavourite(&self, id: &PostId) -> Result<(), MastodonError> {
+        matsu!(self.status_action(id, "unfavourite", MastodonError::FavouriteSendError, MastodonError::FavouriteServerReject))
+    }
+
+    ///Prepares request to bookmark status.
+    pub async fn bookmark(&self, id: &PostId) -> Result<(), MastodonError> {
+        matsu!(self.status_action(id, "bookmark", MastodonError::BookmarkSendError, MastodonError::BookmarkServerReject))
+    }
+
+    ///Prepares request to remove status from bookmarks.
+    pub async fn unbookmark(&self, id: &PostId) -> Result<(), MastodonError> {
+        matsu!(self.status_action(id, "unbookmark", MastodonError::BookmarkSendError, MastodonError::BookmarkServerReject))
+    }
 }
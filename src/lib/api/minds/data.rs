@@ -2,7 +2,7 @@
 
 use serde_derive::{Serialize, Deserialize};
 
-use crate::data::PostFlags;
+use crate::data::{Page, PostFlags};
 
 ///Auth payload
 #[derive(Serialize, Debug)]
@@ -25,12 +25,42 @@ impl<'a> Auth<'a> {
     }
 }
 
+///Error in response to login
+#[derive(Deserialize, Debug)]
+pub struct LoginError {
+    ///Identifier of error, usually name of exception
+    #[serde(rename = "errorId", default)]
+    pub error_id: String,
+}
+
+///Refresh token payload
+#[derive(Serialize, Debug)]
+pub struct Refresh<'a> {
+    grant_type: &'static str,
+    client_id: &'static str,
+    refresh_token: &'a str,
+}
+
+impl<'a> Refresh<'a> {
+    ///Creates new payload
+    pub fn new(refresh_token: &'a str) -> Self {
+        Refresh {
+            grant_type: "refresh_token",
+            client_id: "mobile",
+            refresh_token,
+        }
+    }
+}
+
 ///Payload for successful authorization
 #[derive(Deserialize, Debug)]
 pub struct Oauth2 {
     ///Access token
     pub access_token: String,
-    ///Expiration time(units?)
+    ///Refresh token
+    #[serde(default)]
+    pub refresh_token: String,
+    ///Number of seconds until access token expires
     pub expires_in: u64,
     ///Request's textual status
     pub status: String,
@@ -46,7 +76,7 @@ pub struct Post<'a> {
     description: Option<String>,
     thumbnail: Option<String>,
     url: Option<String>,
-    attachment_guid: &'a Option<String>,
+    attachment_guids: &'a [String],
     ///Whether content is safe for work or not
     pub mature: u8,
     access_id: u8,
@@ -54,7 +84,7 @@ pub struct Post<'a> {
 
 impl<'a> Post<'a> {
     ///Creates new post
-    pub fn new(message: &'a str, attachment_guid: &'a Option<String>, flags: &PostFlags) -> Self {
+    pub fn new(message: &'a str, attachment_guids: &'a [String], flags: &PostFlags) -> Self {
         Post {
             wire_threshold: None,
             message,
@@ -63,7 +93,7 @@ impl<'a> Post<'a> {
             description: None,
             thumbnail: None,
             url: None,
-            attachment_guid,
+            attachment_guids,
             mature: flags.nsfw as u8,
             access_id: 2,
         }
@@ -76,3 +106,137 @@ pub struct UploadResponse {
     ///Newly created entity ID
     pub guid: String,
 }
+
+///Channel's information
+#[derive(Deserialize, Debug, Default)]
+pub struct Channel {
+    ///Channel's ID
+    pub guid: String,
+    ///Username
+    pub username: String,
+    ///Display name
+    pub name: String,
+}
+
+///Response to channel lookup
+#[derive(Deserialize, Debug)]
+pub struct ChannelResponse {
+    ///Requested channel
+    pub channel: Channel,
+}
+
+///Query to fetch page of feed
+#[derive(Serialize, Debug)]
+pub struct FeedQuery<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<&'a str>,
+}
+
+impl<'a> FeedQuery<'a> {
+    ///Creates new instance
+    pub fn new(page: &'a Page) -> Self {
+        Self {
+            limit: page.limit,
+            offset: page.max_id.as_deref(),
+        }
+    }
+}
+
+///Attached image
+#[derive(Deserialize, Debug)]
+pub struct BatchImage {
+    ///URL of image
+    pub src: String,
+}
+
+///Custom data of activity
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum CustomData {
+    ///Attached images
+    Batch(Vec<BatchImage>),
+    ///Anything else
+    Other(serde::de::IgnoredAny),
+}
+
+impl Default for CustomData {
+    fn default() -> Self {
+        CustomData::Other(serde::de::IgnoredAny)
+    }
+}
+
+///Activity from newsfeed
+#[derive(Deserialize, Debug)]
+pub struct Activity {
+    ///Activity's ID
+    pub guid: String,
+    ///Text content
+    #[serde(default)]
+    pub message: String,
+    ///Unix timestamp of creation
+    pub time_created: String,
+    ///Author
+    #[serde(rename = "ownerObj", default)]
+    pub owner: Channel,
+    ///Attachments
+    #[serde(default)]
+    pub custom_data: CustomData,
+}
+
+impl Activity {
+    ///Converts into generic status.
+    ///
+    ///Returns `None` if creation time is invalid.
+    pub fn into_status(self) -> Option<crate::data::Status> {
+        let created_at = self.time_created.parse::<i64>().ok()?;
+        let media = match self.custom_data {
+            CustomData::Batch(images) => images.into_iter().map(|image| image.src).collect(),
+            CustomData::Other(_) => Vec::new(),
+        };
+
+        Some(crate::data::Status {
+            platform: crate::data::Platform::Minds,
+            id: self.guid.into(),
+            author: crate::data::Account {
+                id: self.owner.guid,
+                username: self.owner.username,
+                name: self.owner.name,
+            },
+            text: self.message,
+            created_at: time::OffsetDateTime::from_unix_timestamp(created_at),
+            media,
+        })
+    }
+}
+
+///Response with newsfeed
+#[derive(Deserialize, Debug)]
+pub struct FeedResponse {
+    ///Activities
+    #[serde(default)]
+    pub activity: Vec<Activity>,
+    ///Offset to fetch next page
+    #[serde(rename = "load-next", default)]
+    pub load_next: String,
+}
+
+///Notification
+#[derive(Deserialize, Debug)]
+pub struct Notification {
+    ///Activity in which user is mentioned
+    #[serde(rename = "entityObj")]
+    pub entity: Option<Activity>,
+}
+
+///Response with notifications
+#[derive(Deserialize, Debug)]
+pub struct NotificationsResponse {
+    ///Notifications
+    #[serde(default)]
+    pub notifications: Vec<Notification>,
+    ///Offset to fetch next page
+    #[serde(rename = "load-next", default)]
+    pub load_next: String,
+}
@@ -7,6 +7,16 @@ use std::fmt;
 pub enum MindsError {
     ///Authorization failed.
     LoginFailed,
+    ///There is no valid token and no password to login with.
+    LoginRequired,
+    ///Username or password is wrong.
+    WrongCredentials,
+    ///Account has two-factor authentication enabled, but code is not provided.
+    TwoFactorRequired,
+    ///Two-factor authentication code is wrong.
+    WrongTwoFactorCode,
+    ///Too many login attempts.
+    RateLimited,
     ///Failed to send request to upload image.
     ImageUploadSendError,
     ///Server rejected image upload.
@@ -15,6 +25,10 @@ pub enum MindsError {
     ///
     ///Should contain `id`
     ImageUploadInvalidResponse,
+    ///Failed to send request to delete uploaded media.
+    MediaDeleteSendError,
+    ///Server rejected deletion of uploaded media.
+    MediaDeleteServerReject,
     ///Failed to send request to perform text post.
     PostUploadSendError,
     ///Server rejected posting.
@@ -23,6 +37,34 @@ pub enum MindsError {
     ///
     ///Should contain `id`
     PostUploadInvalidResponse,
+    ///Failed to send request to edit post.
+    PostEditSendError,
+    ///Server rejected post's edit.
+    PostEditServerReject,
+    ///Server responded with invalid data
+    ///
+    ///Should contain `id`
+    PostEditInvalidResponse,
+    ///Failed to send request to verify credentials.
+    VerifyCredentialsSendError,
+    ///Server rejected credentials.
+    VerifyCredentialsServerReject,
+    ///Server responded with invalid data.
+    ///
+    ///Should contain account's information
+    VerifyCredentialsInvalidResponse,
+    ///Failed to send request to fetch timeline.
+    TimelineSendError,
+    ///Server rejected timeline request.
+    TimelineServerReject,
+    ///Server responded with invalid data.
+    ///
+    ///Should contain list of posts
+    TimelineInvalidResponse,
+    ///Failed to send request to favourite or unfavourite post.
+    FavouriteSendError,
+    ///Server rejected to favourite or unfavourite post.
+    FavouriteServerReject,
 
 }
 
@@ -36,12 +78,30 @@ impl Error for MindsError {
     fn description(&self) -> &str {
         match self {
             &MindsError::LoginFailed => "Login has failed",
+            &MindsError::LoginRequired => "Token is missing or cannot be refreshed, and password is not specified. Run `fie auth minds` to login",
+            &MindsError::WrongCredentials => "Login has failed due to wrong username or password",
+            &MindsError::TwoFactorRequired => "Login requires two-factor authentication code",
+            &MindsError::WrongTwoFactorCode => "Login has failed due to wrong two-factor authentication code",
+            &MindsError::RateLimited => "Login has failed due to too many attempts. Try again later",
             &MindsError::ImageUploadSendError => "Failed to send request to upload image",
             &MindsError::ImageUploadServerReject => "Server rejected upload of image",
             &MindsError::ImageUploadInvalidResponse => "Server sent invalid response. Doesn't contain field id",
+            &MindsError::MediaDeleteSendError => "Failed to send request to delete uploaded media",
+            &MindsError::MediaDeleteServerReject => "Server rejected deletion of uploaded media",
             &MindsError::PostUploadSendError => "Failed to send request to perform text post",
             &MindsError::PostUploadServerReject => "Server rejected posting",
             &MindsError::PostUploadInvalidResponse => "Server sent invalid response. Doesn't contain field id",
+            &MindsError::PostEditSendError => "Failed to send request to edit post",
+            &MindsError::PostEditServerReject => "Server rejected edit of post",
+            &MindsError::PostEditInvalidResponse => "Server sent invalid response. Doesn't contain field id",
+            &MindsError::VerifyCredentialsSendError => "Failed to send request to verify credentials",
+            &MindsError::VerifyCredentialsServerReject => "Server rejected credentials",
+            &MindsError::VerifyCredentialsInvalidResponse => "Server sent invalid response. Doesn't contain account information",
+            &MindsError::TimelineSendError => "Failed to send request to fetch timeline",
+            &MindsError::TimelineServerReject => "Server rejected timeline request",
+            &MindsError::TimelineInvalidResponse => "Server sent invalid response. Doesn't contain list of posts",
+            &MindsError::FavouriteSendError => "Failed to send request to favourite post",
+            &MindsError::FavouriteServerReject => "Server rejected to favourite post",
         }
     }
 }
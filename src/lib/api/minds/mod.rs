@@ -1,10 +1,15 @@
 //!Gab API
 
-const OAUTH2_URL: &'static str = "https://www.minds.com/api/v2/oauth/token";
-const IMAGES_URL: &'static str = "https://www.minds.com/api/v1/media";
-const POST_URL: &'static str = "https://www.minds.com/api/v1/newsfeed";
+const OAUTH2_URL: &str = "https://www.minds.com/api/v2/oauth/token";
+const IMAGES_URL: &str = "https://www.minds.com/api/v1/media";
+const POST_URL: &str = "https://www.minds.com/api/v3/newsfeed/activity";
+const FEED_URL: &str = "https://www.minds.com/api/v1/newsfeed/network";
+const MENTIONS_URL: &str = "https://www.minds.com/api/v1/notifications/tags";
+const VOTES_URL: &str = "https://www.minds.com/api/v1/votes";
+const CHANNEL_URL: &str = "https://www.minds.com/api/v1/channel/me";
 
-use crate::data::PostFlags;
+use crate::config::OAuth2Token;
+use crate::data::{Page, PostFlags, PostId, Timeline};
 use super::http::{multipart, GlobalRequest, Mime, Request, matsu};
 
 pub mod data;
@@ -13,19 +18,116 @@ mod error;
 use data::*;
 pub use error::MindsError;
 
+///Code of two-factor authentication.
+struct TwoFactor<'a> {
+    code: &'a str,
+    ///Key, that is issued when code is sent via SMS.
+    sms_key: Option<&'a str>,
+}
+
+enum TokenResponse {
+    Token(Minds),
+    TwoFactor {
+        sms_key: Option<String>,
+    },
+}
+
 ///Minds API
 pub struct Minds {
-    token: String,
+    token: OAuth2Token,
 }
 
 impl Minds {
     ///Creates new instances by attempting to login and get access token.
+    ///
+    ///Previously obtained token is re-used until it expires, and then refreshed.
+    ///Login with password is performed only if there is no valid token.
     pub async fn new(config: crate::config::Minds) -> Result<Self, MindsError> {
-        let req = Request::post(OAUTH2_URL).expect("To create request")
-                                           .json(&Auth::new(&config.username, &config.password))
-                                           .expect("To serialize json")
-                                           .global()
-                                           .send();
+        matsu!(Self::new_with(config, || None))
+    }
+
+    ///Creates new instance same as `new`, but asks `two_factor` for code, if password login requires it.
+    pub async fn new_with<F: FnOnce() -> Option<String>>(config: crate::config::Minds, two_factor: F) -> Result<Self, MindsError> {
+        if let Some(token) = config.token {
+            if !token.is_expired() {
+                return Ok(Self { token });
+            }
+
+            if !token.refresh_token.is_empty() {
+                if let Ok(minds) = matsu!(Self::refresh(&token.refresh_token)) {
+                    return Ok(minds);
+                }
+            }
+        }
+
+        if config.password.is_empty() {
+            return Err(MindsError::LoginRequired);
+        }
+
+        matsu!(Self::login_with(&config.username, &config.password, two_factor))
+    }
+
+    ///Performs login with username and password.
+    ///
+    ///Fails with `MindsError::TwoFactorRequired`, if account has two-factor authentication enabled.
+    pub async fn login(username: &str, password: &str) -> Result<Self, MindsError> {
+        matsu!(Self::login_with(username, password, || None))
+    }
+
+    ///Performs login with username and password.
+    ///
+    ///If account has two-factor authentication enabled, `two_factor` is asked for code.
+    ///Returning `None` aborts login with `MindsError::TwoFactorRequired`.
+    pub async fn login_with<F: FnOnce() -> Option<String>>(username: &str, password: &str, two_factor: F) -> Result<Self, MindsError> {
+        let auth = Auth::new(username, password);
+
+        let sms_key = match matsu!(Self::request_token(&auth, None))? {
+            TokenResponse::Token(minds) => return Ok(minds),
+            TokenResponse::TwoFactor { sms_key } => sms_key,
+        };
+
+        let code = match two_factor() {
+            Some(code) => code,
+            None => return Err(MindsError::TwoFactorRequired),
+        };
+
+        match matsu!(Self::request_token(&auth, Some(TwoFactor { code: code.trim(), sms_key: sms_key.as_deref() })))? {
+            TokenResponse::Token(minds) => Ok(minds),
+            TokenResponse::TwoFactor { .. } => Err(MindsError::WrongTwoFactorCode),
+        }
+    }
+
+    ///Obtains new access token, using refresh token of previous login.
+    pub async fn refresh(refresh_token: &str) -> Result<Self, MindsError> {
+        let mut minds = match matsu!(Self::request_token(&Refresh::new(refresh_token), None))? {
+            TokenResponse::Token(minds) => minds,
+            TokenResponse::TwoFactor { .. } => return Err(MindsError::TwoFactorRequired),
+        };
+
+        //Server may keep using the same refresh token.
+        if minds.token.refresh_token.is_empty() {
+            minds.token.refresh_token.push_str(refresh_token);
+        }
+
+        Ok(minds)
+    }
+
+    async fn request_token<T: serde::Serialize>(payload: &T, two_factor: Option<TwoFactor<'_>>) -> Result<TokenResponse, MindsError> {
+        const TWO_FACTOR_CODE: &str = "X-MINDS-2FA-CODE";
+        const SMS_KEY: &str = "X-MINDS-SMS-2FA-KEY";
+
+        let mut req = Request::post(OAUTH2_URL).expect("To create request");
+        if let Some(two_factor) = two_factor {
+            req = req.set_header(TWO_FACTOR_CODE, two_factor.code);
+            if let Some(sms_key) = two_factor.sms_key {
+                req = req.set_header(SMS_KEY, sms_key);
+            }
+        }
+
+        let req = req.json(payload)
+                     .expect("To serialize json")
+                     .global()
+                     .send();
 
         let mut resp = match matsu!(req) {
             Ok(resp) => match resp {
@@ -35,8 +137,20 @@ impl Minds {
             Err(_) => return Err(MindsError::LoginFailed),
         };
 
-        if !resp.is_success() {
-            return Err(MindsError::LoginFailed);
+        match resp.status().as_u16() {
+            429 => return Err(MindsError::RateLimited),
+            401 | 403 => {
+                let sms_key = resp.headers().get(SMS_KEY).and_then(|key| key.to_str().ok()).map(str::to_owned);
+                let error = matsu!(resp.json::<LoginError>()).map(|error| error.error_id).unwrap_or_default();
+
+                return match error.rsplit("::").next() {
+                    Some("TwoFactorRequiredException") => Ok(TokenResponse::TwoFactor { sms_key }),
+                    Some("TwoFactorInvalidCodeException") => Err(MindsError::WrongTwoFactorCode),
+                    _ => Err(MindsError::WrongCredentials),
+                };
+            },
+            _ if !resp.is_success() => return Err(MindsError::LoginFailed),
+            _ => (),
         }
 
         let oauth2 = match matsu!(resp.json::<Oauth2>()) {
@@ -44,7 +158,20 @@ impl Minds {
             Err(_) => return Err(MindsError::LoginFailed),
         };
 
-        Ok(Self { token: oauth2.access_token })
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|now| now.as_secs()).unwrap_or(0);
+
+        Ok(TokenResponse::Token(Self {
+            token: OAuth2Token {
+                access_token: oauth2.access_token,
+                refresh_token: oauth2.refresh_token,
+                expires_at: now + oauth2.expires_in,
+            }
+        }))
+    }
+
+    ///Returns OAuth tokens, that can be used for the next login.
+    pub fn token(&self) -> &OAuth2Token {
+        &self.token
     }
 
     ///Prepares image upload request.
@@ -54,7 +181,7 @@ impl Minds {
         let mut form = multipart::Form::new();
         form.add_file_field("file".to_string(), name.to_string(), mime, data);
 
-        let req = Request::post(IMAGES_URL).expect("To create request").bearer_auth(&self.token).multipart(form).global().send();
+        let req = Request::post(IMAGES_URL).expect("To create request").bearer_auth(&self.token.access_token).multipart(form).global().send();
 
         // For image we wait twice of time
         // just to be sure
@@ -77,12 +204,36 @@ impl Minds {
         }
     }
 
+    ///Prepares request to delete uploaded media.
+    ///
+    ///Used to clean up media, that is not going to be attached to post.
+    pub async fn delete_media(&self, id: &str) -> Result<(), MindsError> {
+        let url = format!("{}/{}", IMAGES_URL, id);
+        let req = Request::delete(url).expect("To create request")
+                                      .bearer_auth(&self.token.access_token)
+                                      .empty()
+                                      .global()
+                                      .send();
+
+        let resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(MindsError::MediaDeleteSendError),
+        }.map_err(|_| MindsError::MediaDeleteSendError)?;
+
+        match resp.is_success() {
+            true => Ok(()),
+            false => Err(MindsError::MediaDeleteServerReject),
+        }
+    }
+
     ///Prepares post upload request.
-    pub async fn post(&self, message: &str, media_attachments: Option<String>, flags: &PostFlags) -> Result<crate::data::PostId, MindsError> {
-        let req = Request::post(POST_URL).expect("To create request")
-                                         .bearer_auth(&self.token)
-                                         .json(&Post::new(&message, &media_attachments, &flags))
-                                         .expect("To serialzie post data")
+    ///
+    ///Activity can have multiple images attached.
+    pub async fn post(&self, message: &str, media_attachments: &[String], flags: &PostFlags) -> Result<crate::data::PostId, MindsError> {
+        let req = Request::put(POST_URL).expect("To create request")
+                                         .bearer_auth(&self.token.access_token)
+                                         .json(&Post::new(message, media_attachments, flags))
+                                         .expect("To serialize post data")
                                          .global()
                                          .send();
 
@@ -104,4 +255,182 @@ impl Minds {
             Err(_) => Err(MindsError::PostUploadInvalidResponse),
         }
     }
+
+    ///Prepares post edit request.
+    ///
+    ///Replaces message, attachments and mature flag of existing activity.
+    pub async fn edit(&self, id: &PostId, message: &str, media_attachments: &[String], flags: &PostFlags) -> Result<PostId, MindsError> {
+        let url = format!("{}/{}", POST_URL, id);
+        let req = Request::post(url).expect("To create request")
+                                    .bearer_auth(&self.token.access_token)
+                                    .json(&Post::new(message, media_attachments, flags))
+                                    .expect("To serialize post data")
+                                    .global()
+                                    .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(err) => match matsu!(matsu!(err)) {
+                Ok(resp) => resp,
+                Err(_) => return Err(MindsError::PostEditSendError)
+            }
+        }.map_err(|_| MindsError::PostEditSendError)?;
+
+
+        if !resp.is_success() {
+            return Err(MindsError::PostEditServerReject)
+        }
+
+        match matsu!(resp.json::<UploadResponse>()) {
+            Ok(data) => Ok(data.guid.into()),
+            Err(_) => Err(MindsError::PostEditInvalidResponse),
+        }
+    }
+
+    ///Prepares credentials verification request.
+    ///
+    ///Looks up channel that belongs to logged in user.
+    pub async fn verify_credentials(&self) -> Result<crate::data::Account, MindsError> {
+        let req = Request::get(CHANNEL_URL).expect("To create request")
+                                           .bearer_auth(&self.token.access_token)
+                                           .empty()
+                                           .global()
+                                           .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(MindsError::VerifyCredentialsSendError),
+        }.map_err(|_| MindsError::VerifyCredentialsSendError)?;
+
+        if !resp.is_success() {
+            return Err(MindsError::VerifyCredentialsServerReject)
+        }
+
+        match matsu!(resp.json::<ChannelResponse>()) {
+            Ok(data) => Ok(crate::data::Account {
+                id: data.channel.guid,
+                username: data.channel.username,
+                name: data.channel.name,
+            }),
+            Err(_) => Err(MindsError::VerifyCredentialsInvalidResponse),
+        }
+    }
+
+    ///Prepares request to fetch newsfeed of subscriptions.
+    ///
+    ///`Page::since_id` is not supported.
+    pub async fn home_timeline(&self, page: &Page) -> Result<Timeline, MindsError> {
+        let req = Request::get(FEED_URL).expect("To create request")
+                                        .bearer_auth(&self.token.access_token)
+                                        .query(&FeedQuery::new(page))
+                                        .empty()
+                                        .global()
+                                        .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(MindsError::TimelineSendError),
+        }.map_err(|_| MindsError::TimelineSendError)?;
+
+        if !resp.is_success() {
+            return Err(MindsError::TimelineServerReject)
+        }
+
+        let feed = match matsu!(resp.json::<FeedResponse>()) {
+            Ok(feed) => feed,
+            Err(_) => return Err(MindsError::TimelineInvalidResponse),
+        };
+
+        let mut statuses = Vec::with_capacity(feed.activity.len());
+        for activity in feed.activity {
+            match activity.into_status() {
+                Some(status) => statuses.push(status),
+                None => return Err(MindsError::TimelineInvalidResponse),
+            }
+        }
+
+        Ok(Timeline {
+            statuses,
+            next: Some(feed.load_next).filter(|next| !next.is_empty()),
+        })
+    }
+
+    ///Prepares request to fetch activities that mention user.
+    ///
+    ///`Page::since_id` is not supported.
+    pub async fn mentions(&self, page: &Page) -> Result<Timeline, MindsError> {
+        let req = Request::get(MENTIONS_URL).expect("To create request")
+                                            .bearer_auth(&self.token.access_token)
+                                            .query(&FeedQuery::new(page))
+                                            .empty()
+                                            .global()
+                                            .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(MindsError::TimelineSendError),
+        }.map_err(|_| MindsError::TimelineSendError)?;
+
+        if !resp.is_success() {
+            return Err(MindsError::TimelineServerReject)
+        }
+
+        let notifications = match matsu!(resp.json::<NotificationsResponse>()) {
+            Ok(notifications) => notifications,
+            Err(_) => return Err(MindsError::TimelineInvalidResponse),
+        };
+
+        let mut statuses = Vec::with_capacity(notifications.notifications.len());
+        for activity in notifications.notifications.into_iter().filter_map(|notification| notification.entity) {
+            match activity.into_status() {
+                Some(status) => statuses.push(status),
+                None => return Err(MindsError::TimelineInvalidResponse),
+            }
+        }
+
+        Ok(Timeline {
+            statuses,
+            next: Some(notifications.load_next).filter(|next| !next.is_empty()),
+        })
+    }
+
+    ///Prepares request to vote up activity.
+    pub async fn favourite(&self, id: &PostId) -> Result<(), MindsError> {
+        let url = format!("{}/{}/up", VOTES_URL, id);
+        let req = Request::put(url).expect("To create request")
+                                   .bearer_auth(&self.token.access_token)
+                                   .empty()
+                                   .global()
+                                   .send();
+
+        let resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(MindsError::FavouriteSendError),
+        }.map_err(|_| MindsError::FavouriteSendError)?;
+
+        match resp.is_success() {
+            true => Ok(()),
+            false => Err(MindsError::FavouriteServerReject),
+        }
+    }
+
+    ///Prepares request to cancel up vote of activity.
+    pub async fn unfavourite(&self, id: &PostId) -> Result<(), MindsError> {
+        let url = format!("{}/{}/up", VOTES_URL, id);
+        let req = Request::delete(url).expect("To create request")
+                                      .bearer_auth(&self.token.access_token)
+                                      .empty()
+                                      .global()
+                                      .send();
+
+        let resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(MindsError::FavouriteSendError),
+        }.map_err(|_| MindsError::FavouriteSendError)?;
+
+        match resp.is_success() {
+            true => Ok(()),
+            false => Err(MindsError::FavouriteServerReject),
+        }
+    }
 }
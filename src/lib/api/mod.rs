@@ -1,6 +1,7 @@
 //!Social medias API module
 
 pub mod http;
+pub mod cache;
 pub mod twitter;
 pub mod gab;
 pub mod mastodon;
@@ -11,11 +12,20 @@ use gab::{Gab, GabError};
 use mastodon::{Mastodon, MastodonError};
 use minds::{Minds, MindsError};
 use http::{matsu};
-use crate::data::{join_hash_tags, PostId, Post};
+use cache::UploadCache;
+use crate::data::metadata::{self, MetadataFilter};
+use crate::data::{join_hash_tags, Account, Attachment, AttachmentKind, AttachmentViolation, Focus, Image, Page, Platform, PostFlags, PostId, PostRef, PostRefs, Post, Timeline};
 
 use super::config;
 
+use mime_guess::Mime;
+
 use core::fmt;
+use core::future::Future;
+use futures_util::{future, stream, StreamExt};
+use std::borrow::Cow;
+use std::str::FromStr;
+use std::sync::Mutex;
 use std::error::Error;
 use std::io;
 
@@ -24,6 +34,12 @@ use std::io;
 pub enum ApiError {
     ///Unable to load Image for attachment
     CannotLoadImage(String, io::Error),
+    ///Attachment's content is not recognized as supported media.
+    UnsupportedAttachment(String),
+    ///Image is malformed, hence its metadata cannot be stripped.
+    ///
+    ///Such image is never uploaded as it is, because it may still carry metadata.
+    CannotStripMetadata(String),
     ///Twitter error
     Twitter(TwitterError),
     ///Gab error
@@ -32,16 +48,29 @@ pub enum ApiError {
     Mastodon(MastodonError),
     ///Minds error
     Minds(MindsError),
+    ///Social media doesn't support requested operation.
+    ///
+    ///Contains name of social media and operation's description.
+    Unsupported(&'static str, &'static str),
+    ///Social media is not enabled.
+    Disabled(Platform),
+    ///Attachments exceed restrictions of social media.
+    InvalidAttachments(Platform, AttachmentViolation),
 }
 
 impl fmt::Display for ApiError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            &ApiError::CannotLoadImage(ref name, ref error) => write!(f, "Error opening image '{}'. Error: {}", name, error),
-            &ApiError::Twitter(ref error) => write!(f, "Twitter API Error: {}", error),
-            &ApiError::Gab(ref error) => write!(f, "Gab API Error: {}", error),
-            &ApiError::Mastodon(ref error) => write!(f, "Mastodon API Error: {}", error),
-            &ApiError::Minds(ref error) => write!(f, "MindsError API Error: {}", error),
+            ApiError::CannotLoadImage(name, error) => write!(f, "Error opening image '{}'. Error: {}", name, error),
+            ApiError::UnsupportedAttachment(name) => write!(f, "Attachment '{}' is not supported. It should be image, video or audio", name),
+            ApiError::CannotStripMetadata(name) => write!(f, "Unable to strip metadata of image '{}', as it is malformed. Disable stripping to upload it anyway", name),
+            ApiError::Twitter(error) => write!(f, "Twitter API Error: {}", error),
+            ApiError::Gab(error) => write!(f, "Gab API Error: {}", error),
+            ApiError::Mastodon(error) => write!(f, "Mastodon API Error: {}", error),
+            ApiError::Minds(error) => write!(f, "MindsError API Error: {}", error),
+            ApiError::Unsupported(platform, operation) => write!(f, "{} doesn't support {}", platform, operation),
+            ApiError::Disabled(platform) => write!(f, "{} is not enabled", platform),
+            ApiError::InvalidAttachments(platform, violation) => write!(f, "{} cannot accept attachments: {}", platform, violation),
         }
     }
 }
@@ -72,7 +101,92 @@ impl From<MindsError> for ApiError {
     }
 }
 
-type PostResultInner = (Option<Result<PostId, ApiError>>, Option<Result<PostId, ApiError>>, Option<Result<PostId, ApiError>>, Option<Result<PostId, ApiError>>);
+type ApiResultInner<T> = (Option<Result<T, ApiError>>, Option<Result<T, ApiError>>, Option<Result<T, ApiError>>, Option<Result<T, ApiError>>);
+
+///Appends hashtags as the last line of message.
+fn format_message(message: String, tags: &[String]) -> String {
+    if !tags.is_empty() {
+        match message.as_str() {
+            "" => join_hash_tags(tags),
+            message => format!("{}\n{}", message, join_hash_tags(tags)),
+        }
+    } else {
+        message
+    }
+}
+
+///Loads all images to attach.
+///
+///Fails if any image is not recognized as supported media.
+fn open_images(mut images: Vec<Attachment>) -> Result<Vec<Image>, ApiError> {
+    let mut result = Vec::with_capacity(images.len());
+    for image in images.drain(..) {
+        let name = image.name();
+        match image.load() {
+            Ok(loaded) => match loaded.kind() {
+                AttachmentKind::Unknown => return Err(ApiError::UnsupportedAttachment(name)),
+                _ => result.push(loaded),
+            },
+            Err(error) => {
+                return Err(ApiError::CannotLoadImage(name, error));
+            },
+        };
+    }
+
+    Ok(result)
+}
+
+///Attachment's content, prepared for upload to particular social media.
+struct Upload<'a> {
+    name: &'a str,
+    mime: Mime,
+    data: Cow<'a, [u8]>,
+    focus: Option<Focus>,
+    sensitive: bool,
+}
+
+impl<'a> Upload<'a> {
+    fn kind(&self) -> AttachmentKind {
+        AttachmentKind::from_mime(&self.mime, &self.data)
+    }
+}
+
+///Marks post as NSFW, if any of attachments is sensitive.
+fn post_flags(flags: &PostFlags, uploads: &[Upload]) -> PostFlags {
+    PostFlags {
+        nsfw: flags.nsfw || uploads.iter().any(|upload| upload.sensitive),
+    }
+}
+
+///Uploads attachments concurrently, preserving order of resulting IDs.
+///
+///At most `concurrency` uploads are performed at the same time.
+///If any upload fails, successfully uploaded media is removed using `cleanup`.
+async fn upload_all<'a, T, E, F, R, C, CR>(uploads: &'a [Upload<'a>], concurrency: usize, upload: F, cleanup: C) -> Result<Vec<T>, E>
+    where F: Fn(&'a Upload<'a>) -> R, R: Future<Output=Result<T, E>>, C: Fn(T) -> CR, CR: Future
+{
+    let results: Vec<Result<T, E>> = matsu!(stream::iter(uploads.iter()).map(upload).buffered(concurrency.max(1)).collect());
+
+    let mut ids = Vec::with_capacity(results.len());
+    let mut error = None;
+    for result in results {
+        match result {
+            Ok(id) => ids.push(id),
+            Err(err) => if error.is_none() {
+                error = Some(err);
+            },
+        }
+    }
+
+    match error {
+        None => Ok(ids),
+        Some(error) => {
+            //Best effort, as media that is never attached expires anyway.
+            matsu!(future::join_all(ids.into_iter().map(cleanup)));
+            Err(error)
+        },
+    }
+}
 
 async fn post_result<T, E: Into<ApiError>, F: core::future::Future<Output=Result<T, E>>>(post: Option<F>) -> Option<Result<T, ApiError>> {
     match post {
@@ -81,67 +195,92 @@ async fn post_result<T, E: Into<ApiError>, F: core::future::Future<Output=Result
     }
 }
 
-///Result of Post.
-pub struct PostResult {
-    inner: PostResultInner,
+///Result of operation performed on each enabled social media.
+pub struct ApiResult<T> {
+    inner: ApiResultInner<T>,
 }
 
-impl PostResult {
+impl<T> ApiResult<T> {
     ///Retrieves Twitter's result
-    pub fn twitter(&mut self) -> Option<Result<PostId, ApiError>> {
+    pub fn twitter(&mut self) -> Option<Result<T, ApiError>> {
         self.inner.0.take()
     }
 
     ///Retrieves Gab's result
-    pub fn gab(&mut self) -> Option<Result<PostId, ApiError>> {
+    pub fn gab(&mut self) -> Option<Result<T, ApiError>> {
         self.inner.1.take()
     }
 
     ///Retrieves Mastodon's result
-    pub fn mastodon(&mut self) -> Option<Result<PostId, ApiError>> {
+    pub fn mastodon(&mut self) -> Option<Result<T, ApiError>> {
         self.inner.2.take()
     }
 
     ///Retrieves Minds's result
-    pub fn minds(&mut self) -> Option<Result<PostId, ApiError>> {
+    pub fn minds(&mut self) -> Option<Result<T, ApiError>> {
         self.inner.3.take()
     }
 
     ///Retrieves underlying errors.
     ///
     ///Order: Twitter, Gab, Mastodon, Minds
-    pub fn into_parts(self) -> PostResultInner {
+    pub fn into_parts(self) -> ApiResultInner<T> {
         self.inner
     }
 }
 
+///Result of Post.
+pub type PostResult = ApiResult<PostId>;
+
+///Result of credentials verification.
+pub type AccountResult = ApiResult<Account>;
+
+///Result of timeline request.
+pub type TimelineResult = ApiResult<Timeline>;
+
 ///API access
 pub struct API {
     twitter: Option<Twitter>,
     gab: Option<Gab>,
     mastodon: Option<Mastodon>,
     minds: Option<Minds>,
+    metadata: Option<MetadataFilter>,
+    attachment_policy: config::AttachmentPolicy,
+    upload_concurrency: usize,
+    upload_cache: Mutex<UploadCache>,
 }
 
 impl API {
     ///Creates new API access module by reading configuration data.
     pub fn new(settings: config::Settings) -> Self {
         http::set_timeout(&settings);
+        let metadata = match settings.strip_metadata {
+            true => Some(MetadataFilter {
+                keep_orientation: settings.keep_orientation,
+                keep_icc_profile: settings.keep_icc_profile,
+            }),
+            false => None,
+        };
+
         Self {
             twitter: None,
             mastodon: None,
             gab: None,
             minds: None,
+            metadata,
+            attachment_policy: settings.attachment_policy,
+            upload_concurrency: settings.upload_concurrency,
+            upload_cache: Mutex::new(UploadCache::new()),
         }
     }
 
     ///Performs initial configuration of Twitter API.
-    pub fn configure_twitter(&mut self, config: config::Twitter) -> Result<(), ApiError> {
+    pub async fn configure_twitter(&mut self, config: config::Twitter) -> Result<(), ApiError> {
         if self.twitter.is_some() {
             return Ok(());
         }
 
-        self.twitter = Some(Twitter::new(config)?);
+        self.twitter = Some(matsu!(Twitter::new(config))?);
         Ok(())
     }
 
@@ -155,6 +294,13 @@ impl API {
         self.twitter.take()
     }
 
+    ///Returns Twitter's OAuth 2.0 tokens, that can be used next time.
+    ///
+    ///Available only with API v2.
+    pub fn twitter_token(&self) -> Option<&config::OAuth2Token> {
+        self.twitter.as_ref().and_then(Twitter::token)
+    }
+
     ///Performs initial configuration of Gab API.
     pub fn configure_gab(&mut self, config: config::Gab) -> Result<(), ApiError> {
         if self.gab.is_some() {
@@ -197,11 +343,16 @@ impl API {
 
     ///Performs initial configuration of Minds API.
     pub async fn configure_minds(&mut self, config: config::Minds) -> Result<(), ApiError> {
+        matsu!(self.configure_minds_with(config, || None))
+    }
+
+    ///Performs initial configuration of Minds API, asking `two_factor` for code if login requires it.
+    pub async fn configure_minds_with<F: FnOnce() -> Option<String>>(&mut self, config: config::Minds, two_factor: F) -> Result<(), ApiError> {
         if self.minds.is_some() {
             return Ok(());
         }
 
-        self.minds = Some(matsu!(Minds::new(config))?);
+        self.minds = Some(matsu!(Minds::new_with(config, two_factor))?);
         Ok(())
     }
 
@@ -215,90 +366,428 @@ impl API {
         self.minds.take()
     }
 
-    ///Sends Post to enabled APIs (blocking)
-    pub async fn send(&self, post: Post) -> Result<PostResult, ApiError> {
-        let Post { message, tags, flags, mut images } = post;
+    ///Returns Minds's OAuth tokens, that can be used for the next login.
+    pub fn minds_token(&self) -> Option<&config::OAuth2Token> {
+        self.minds.as_ref().map(Minds::token)
+    }
 
-        let message = if tags.len() > 0 {
-            match message.as_str() {
-                "" => join_hash_tags(&tags),
-                message => format!("{}\n{}", message, join_hash_tags(&tags)),
-            }
-        } else {
-            message
+    ///Returns copy of cache of uploaded media.
+    ///
+    ///Can be serialized to be re-used by another instance of API.
+    pub fn upload_cache(&self) -> UploadCache {
+        self.upload_cache.lock().expect("To lock upload cache").clone()
+    }
+
+    ///Replaces cache of uploaded media.
+    pub fn set_upload_cache(&mut self, mut cache: UploadCache) {
+        cache.purge();
+        self.upload_cache = Mutex::new(cache);
+    }
+
+    ///Performs `request` to upload media, unless the same content is already uploaded.
+    async fn cached_upload<T, E, R>(&self, platform: Platform, upload: &Upload<'_>, request: R) -> Result<T, E> where T: fmt::Display + FromStr, R: Future<Output=Result<T, E>> {
+        if platform.media_lifetime().is_none() {
+            return matsu!(request);
+        }
+
+        let digest = UploadCache::digest(&upload.data);
+        let cached = self.upload_cache.lock().expect("To lock upload cache").get(platform, &digest).and_then(|id| id.parse().ok());
+        if let Some(id) = cached {
+            return Ok(id);
+        }
+
+        let id = matsu!(request)?;
+        self.upload_cache.lock().expect("To lock upload cache").insert(platform, digest, id.to_string());
+        Ok(id)
+    }
+
+    ///Checks attachments against restrictions of social media, before anything is uploaded.
+    ///
+    ///Only platforms that are marked as used are checked.
+    ///Does nothing when attachments are trimmed instead.
+    fn check_attachments(&self, images: &[Image], platforms: &[(bool, Platform)]) -> Result<(), ApiError> {
+        if self.attachment_policy == config::AttachmentPolicy::Trim {
+            return Ok(());
+        }
+
+        for &(_, platform) in platforms.iter().filter(|(used, _)| *used) {
+            platform.capabilities().check(images.iter().map(Image::kind)).map_err(|violation| ApiError::InvalidAttachments(platform, violation))?;
+        }
+
+        Ok(())
+    }
+
+    ///Prepares images for upload to social media.
+    ///
+    ///Attachments that exceed restrictions of social media are dropped, if trimming is enabled.
+    ///With `image-processing` feature images that exceed limits are downscaled.
+    ///Metadata is stripped from the rest of images, if enabled.
+    ///Fails if metadata is to be stripped, but image cannot be parsed.
+    fn prepare_uploads<'a>(&self, images: &'a [Image], platform: Platform) -> Result<Vec<Upload<'a>>, ApiError> {
+        let images = match self.attachment_policy {
+            config::AttachmentPolicy::Trim => platform.capabilities().trim(images),
+            config::AttachmentPolicy::Fail => images.iter().collect(),
         };
 
+        images.into_iter().map(|image| {
+            #[cfg(feature = "image-processing")]
+            {
+                if let Some(processed) = image.fit(platform.image_limits(), self.metadata.is_none_or(|filter| filter.keep_icc_profile)) {
+                    return Ok(Upload { name: &image.name, mime: processed.mime, data: Cow::Owned(processed.data), focus: image.focus, sensitive: image.sensitive });
+                }
+            }
+
+            let stripped = match self.metadata.as_ref() {
+                Some(filter) => metadata::strip(image.data(), &image.mime, filter).map_err(|_| ApiError::CannotStripMetadata(image.name.clone()))?,
+                None => None,
+            };
+            let data = match stripped {
+                Some(stripped) => Cow::Owned(stripped),
+                None => Cow::Borrowed(image.data()),
+            };
+
+            Ok(Upload { name: &image.name, mime: image.mime.clone(), data, focus: image.focus, sensitive: image.sensitive })
+        }).collect()
+    }
+
+    fn twitter(&self) -> Result<&Twitter, ApiError> {
+        self.twitter.as_ref().ok_or(ApiError::Disabled(Platform::Twitter))
+    }
+
+    fn gab(&self) -> Result<&Gab, ApiError> {
+        self.gab.as_ref().ok_or(ApiError::Disabled(Platform::Gab))
+    }
+
+    fn mastodon(&self) -> Result<&Mastodon, ApiError> {
+        self.mastodon.as_ref().ok_or(ApiError::Disabled(Platform::Mastodon))
+    }
+
+    fn minds(&self) -> Result<&Minds, ApiError> {
+        self.minds.as_ref().ok_or(ApiError::Disabled(Platform::Minds))
+    }
+
+    ///Sends Post to enabled APIs (blocking)
+    ///
+    ///Attachments are checked against restrictions of each enabled social media, before anything is uploaded.
+    pub async fn send(&self, post: Post) -> Result<PostResult, ApiError> {
+        let Post { message, tags, flags, images } = post;
+
+        let message = format_message(message, &tags);
         let message = message.as_str();
         let flags = &flags;
+        let images = open_images(images)?;
+        let images = &images[..];
+        self.check_attachments(images, &[
+            (self.twitter.is_some(), Platform::Twitter),
+            (self.gab.is_some(), Platform::Gab),
+            (self.mastodon.is_some(), Platform::Mastodon),
+            (self.minds.is_some(), Platform::Minds),
+        ])?;
+
+        let twitter = post_result(self.twitter.as_ref().map(|twitter| async move {
+            let media = self.prepare_uploads(images, Platform::Twitter)?;
+            let flags = post_flags(flags, &media);
+            //Twitter has no endpoint to delete media, it expires on its own after `media_lifetime`.
+            let uploads = matsu!(upload_all(&media, self.upload_concurrency, |upload| self.cached_upload(Platform::Twitter, upload, twitter.upload_image(upload.name, &upload.mime, &upload.data)), |_| future::ready(())))?;
+            matsu!(twitter.post(message, &uploads, &flags)).map_err(ApiError::from)
+        }));
+
+        let gab = post_result(self.gab.as_ref().map(|gab| async move {
+            let media = self.prepare_uploads(images, Platform::Gab)?;
+            let flags = post_flags(flags, &media);
+            let uploads = matsu!(upload_all(&media, self.upload_concurrency, |upload| self.cached_upload(Platform::Gab, upload, gab.upload_image(upload.name, &upload.mime, &upload.data, upload.focus.as_ref())), |id| async move { matsu!(gab.delete_media(&id)) }))?;
+            matsu!(gab.post(message, &uploads, &flags)).map_err(ApiError::from)
+        }));
+
+        let mastodon = post_result(self.mastodon.as_ref().map(|mastodon| async move {
+            let media = self.prepare_uploads(images, Platform::Mastodon)?;
+            let flags = post_flags(flags, &media);
+            let uploads = matsu!(upload_all(&media, self.upload_concurrency, |upload| self.cached_upload(Platform::Mastodon, upload, mastodon.upload_image(upload.name, &upload.mime, &upload.data, upload.focus.as_ref())), |id| async move { matsu!(mastodon.delete_media(&id)) }))?;
+            matsu!(mastodon.post(message, &uploads, &flags)).map_err(ApiError::from)
+        }));
+
+        let minds = post_result(self.minds.as_ref().map(|minds| async move {
+            let media = self.prepare_uploads(images, Platform::Minds)?;
+            let flags = post_flags(flags, &media);
+            let uploads = matsu!(upload_all(&media, self.upload_concurrency, |upload| self.cached_upload(Platform::Minds, upload, minds.upload_image(upload.name, &upload.mime, &upload.data)), |id| async move { matsu!(minds.delete_media(&id)) }))?;
+            matsu!(minds.post(message, &uploads, &flags)).map_err(ApiError::from)
+        }));
 
-        let inner = match images.len() {
-            0 => {
-                let twitter = post_result(self.twitter.as_ref().map(|twitter| twitter.post(&message, &[], &flags)));
-                let gab = post_result(self.gab.as_ref().map(|gab| gab.post(&message, &[], &flags)));
-                let mastodon = post_result(self.mastodon.as_ref().map(|mastodon| mastodon.post(&message, &[], &flags)));
-                let minds = post_result(self.minds.as_ref().map(|minds| minds.post(&message, None, &flags)));
-                futures_util::join!(twitter, gab, mastodon, minds)
-            },
-            _ => {
-                let images = {
-                    let mut result = vec![];
-                    for image in images.drain(..) {
-                        match crate::data::Image::open(&image) {
-                            Ok(image) => result.push(image),
-                            Err(error) => {
-                                return Err(ApiError::CannotLoadImage(image, error));
-                            },
-                        };
-                    }
-                    result
+        Ok(PostResult {
+            inner: futures_util::join!(twitter, gab, mastodon, minds),
+        })
+    }
+
+    ///Sends Post as direct message to recipient on enabled APIs.
+    ///
+    ///Only platforms, for which recipient has handle, are used.
+    ///Minds doesn't support direct messages, hence it reports `ApiError::Unsupported`.
+    ///Twitter allows only single attachment per message.
+    pub async fn send_direct(&self, recipient: &config::Recipient, post: Post) -> Result<PostResult, ApiError> {
+        let Post { message, tags, flags, images } = post;
+
+        let message = format_message(message, &tags);
+        let message = message.as_str();
+        let flags = &flags;
+        let images = open_images(images)?;
+        let images = &images[..];
+        self.check_attachments(images, &[
+            (self.twitter.is_some() && recipient.twitter.is_some(), Platform::Twitter),
+            (self.gab.is_some() && recipient.gab.is_some(), Platform::Gab),
+            (self.mastodon.is_some() && recipient.mastodon.is_some(), Platform::Mastodon),
+        ])?;
+
+        let twitter = post_result(match (self.twitter.as_ref(), recipient.twitter.as_ref()) {
+            (Some(twitter), Some(recipient)) => Some(async move {
+                //Fail before uploading, so that no media is left orphaned
+                if twitter.version() == config::TwitterVersion::V2 {
+                    return Err(TwitterError::UnsupportedByVersion.into());
+                }
+
+                let media = self.prepare_uploads(images, Platform::Twitter)?;
+                let upload = match &media[..] {
+                    [] => None,
+                    [upload] => Some(matsu!(twitter.upload_media(&upload.mime, &upload.data, twitter::data::MediaCategory::direct(upload.kind())))?),
+                    _ => return Err(ApiError::Unsupported("Twitter", "multiple attachments in direct message")),
                 };
 
-                let images = &images[..];
+                matsu!(twitter.send_direct(recipient, message, upload)).map_err(|error| error.into())
+            }),
+            _ => None,
+        });
+
+        let gab = post_result(match (self.gab.as_ref(), recipient.gab.as_ref()) {
+            (Some(gab), Some(recipient)) => Some(async move {
+                let media = self.prepare_uploads(images, Platform::Gab)?;
+                let flags = post_flags(flags, &media);
+                let uploads = matsu!(upload_all(&media, self.upload_concurrency, |upload| self.cached_upload(Platform::Gab, upload, gab.upload_image(upload.name, &upload.mime, &upload.data, upload.focus.as_ref())), |id| async move { matsu!(gab.delete_media(&id)) }))?;
+                matsu!(gab.send_direct(recipient, message, &uploads, &flags)).map_err(ApiError::from)
+            }),
+            _ => None,
+        });
+
+        let mastodon = post_result(match (self.mastodon.as_ref(), recipient.mastodon.as_ref()) {
+            (Some(mastodon), Some(recipient)) => Some(async move {
+                let media = self.prepare_uploads(images, Platform::Mastodon)?;
+                let flags = post_flags(flags, &media);
+                let uploads = matsu!(upload_all(&media, self.upload_concurrency, |upload| self.cached_upload(Platform::Mastodon, upload, mastodon.upload_image(upload.name, &upload.mime, &upload.data, upload.focus.as_ref())), |id| async move { matsu!(mastodon.delete_media(&id)) }))?;
+                matsu!(mastodon.send_direct(recipient, message, &uploads, &flags)).map_err(ApiError::from)
+            }),
+            _ => None,
+        });
+
+        let minds = post_result(match (self.minds.as_ref(), recipient.minds.as_ref()) {
+            (Some(_), Some(_)) => Some(async {
+                Err::<PostId, _>(ApiError::Unsupported("Minds", "direct messages"))
+            }),
+            _ => None,
+        });
+
+        Ok(PostResult {
+            inner: futures_util::join!(twitter, gab, mastodon, minds),
+        })
+    }
+
+    ///Edits already published post on enabled APIs.
+    ///
+    ///Only platforms that have reference in `refs` are edited.
+    ///Twitter doesn't support editing, hence it reports `ApiError::Unsupported`.
+    pub async fn edit(&self, refs: PostRefs, post: Post) -> Result<PostResult, ApiError> {
+        let PostRefs { twitter, gab, mastodon, minds } = refs;
+        let Post { message, tags, flags, images } = post;
+
+        let message = format_message(message, &tags);
+        let message = message.as_str();
+        let flags = &flags;
+        let images = open_images(images)?;
+        let images = &images[..];
+        self.check_attachments(images, &[
+            (self.gab.is_some() && gab.is_some(), Platform::Gab),
+            (self.mastodon.is_some() && mastodon.is_some(), Platform::Mastodon),
+            (self.minds.is_some() && minds.is_some(), Platform::Minds),
+        ])?;
+
+        let twitter = post_result(match (self.twitter.as_ref(), twitter) {
+            (Some(_), Some(_)) => Some(async {
+                Err::<PostId, _>(ApiError::Unsupported("Twitter", "editing of posts"))
+            }),
+            _ => None,
+        });
+
+        let gab = post_result(match (self.gab.as_ref(), gab) {
+            (Some(gab), Some(id)) => Some(async move {
+                let media = self.prepare_uploads(images, Platform::Gab)?;
+                let flags = post_flags(flags, &media);
+                let uploads = matsu!(upload_all(&media, self.upload_concurrency, |upload| self.cached_upload(Platform::Gab, upload, gab.upload_image(upload.name, &upload.mime, &upload.data, upload.focus.as_ref())), |id| async move { matsu!(gab.delete_media(&id)) }))?;
+                matsu!(gab.edit(&id, message, &uploads, &flags)).map_err(ApiError::from)
+            }),
+            _ => None,
+        });
+
+        let mastodon = post_result(match (self.mastodon.as_ref(), mastodon) {
+            (Some(mastodon), Some(id)) => Some(async move {
+                let media = self.prepare_uploads(images, Platform::Mastodon)?;
+                let flags = post_flags(flags, &media);
+                let uploads = matsu!(upload_all(&media, self.upload_concurrency, |upload| self.cached_upload(Platform::Mastodon, upload, mastodon.upload_image(upload.name, &upload.mime, &upload.data, upload.focus.as_ref())), |id| async move { matsu!(mastodon.delete_media(&id)) }))?;
+                matsu!(mastodon.edit(&id, message, &uploads, &flags)).map_err(ApiError::from)
+            }),
+            _ => None,
+        });
+
+        let minds = post_result(match (self.minds.as_ref(), minds) {
+            (Some(minds), Some(id)) => Some(async move {
+                let media = self.prepare_uploads(images, Platform::Minds)?;
+                let flags = post_flags(flags, &media);
+                let uploads = matsu!(upload_all(&media, self.upload_concurrency, |upload| self.cached_upload(Platform::Minds, upload, minds.upload_image(upload.name, &upload.mime, &upload.data)), |id| async move { matsu!(minds.delete_media(&id)) }))?;
+                matsu!(minds.edit(&id, message, &uploads, &flags)).map_err(ApiError::from)
+            }),
+            _ => None,
+        });
+
+        Ok(PostResult {
+            inner: futures_util::join!(twitter, gab, mastodon, minds),
+        })
+    }
+
+    ///Verifies credentials of enabled APIs.
+    ///
+    ///Result contains account to which credentials belong.
+    pub async fn verify_credentials(&self) -> AccountResult {
+        let twitter = post_result(self.twitter.as_ref().map(|twitter| twitter.verify_credentials()));
+        let gab = post_result(self.gab.as_ref().map(|gab| gab.verify_credentials()));
+        let mastodon = post_result(self.mastodon.as_ref().map(|mastodon| mastodon.verify_credentials()));
+        let minds = post_result(self.minds.as_ref().map(|minds| minds.verify_credentials()));
+
+        ApiResult {
+            inner: futures_util::join!(twitter, gab, mastodon, minds),
+        }
+    }
+
+    ///Fetches home timeline of enabled APIs.
+    pub async fn home_timeline(&self, page: &Page) -> TimelineResult {
+        let twitter = post_result(self.twitter.as_ref().map(|twitter| twitter.home_timeline(page)));
+        let gab = post_result(self.gab.as_ref().map(|gab| gab.home_timeline(page)));
+        let mastodon = post_result(self.mastodon.as_ref().map(|mastodon| mastodon.home_timeline(page)));
+        let minds = post_result(self.minds.as_ref().map(|minds| minds.home_timeline(page)));
+
+        ApiResult {
+            inner: futures_util::join!(twitter, gab, mastodon, minds),
+        }
+    }
+
+    ///Fetches posts that mention user on enabled APIs.
+    pub async fn mentions(&self, page: &Page) -> TimelineResult {
+        let twitter = post_result(self.twitter.as_ref().map(|twitter| twitter.mentions(page)));
+        let gab = post_result(self.gab.as_ref().map(|gab| gab.mentions(page)));
+        let mastodon = post_result(self.mastodon.as_ref().map(|mastodon| mastodon.mentions(page)));
+        let minds = post_result(self.minds.as_ref().map(|minds| minds.mentions(page)));
+
+        ApiResult {
+            inner: futures_util::join!(twitter, gab, mastodon, minds),
+        }
+    }
+
+    ///Favourites post.
+    ///
+    ///Minds uses up vote.
+    pub async fn favourite(&self, post: &PostRef) -> Result<(), ApiError> {
+        match post.platform {
+            Platform::Twitter => matsu!(self.twitter()?.favourite(&post.id))?,
+            Platform::Gab => matsu!(self.gab()?.favourite(&post.id))?,
+            Platform::Mastodon => matsu!(self.mastodon()?.favourite(&post.id))?,
+            Platform::Minds => matsu!(self.minds()?.favourite(&post.id))?,
+        }
+
+        Ok(())
+    }
+
+    ///Removes post from favourites.
+    ///
+    ///Minds cancels up vote.
+    pub async fn unfavourite(&self, post: &PostRef) -> Result<(), ApiError> {
+        match post.platform {
+            Platform::Twitter => matsu!(self.twitter()?.unfavourite(&post.id))?,
+            Platform::Gab => matsu!(self.gab()?.unfavourite(&post.id))?,
+            Platform::Mastodon => matsu!(self.mastodon()?.unfavourite(&post.id))?,
+            Platform::Minds => matsu!(self.minds()?.unfavourite(&post.id))?,
+        }
+
+        Ok(())
+    }
 
-                let twitter = post_result(self.twitter.as_ref().map(|twitter| async move {
-                    let mut uploads = vec![];
-                    for image in images.iter() {
-                        let upload = matsu!(twitter.upload_image(&image.name, &image.mime, &image.mmap[..]))?;
-                        uploads.push(upload);
-                    }
+    ///Bookmarks post.
+    ///
+    ///Only Mastodon supports bookmarks.
+    pub async fn bookmark(&self, post: &PostRef) -> Result<(), ApiError> {
+        match post.platform {
+            Platform::Mastodon => matsu!(self.mastodon()?.bookmark(&post.id))?,
+            Platform::Twitter => return Err(ApiError::Unsupported("Twitter", "bookmarks")),
+            Platform::Gab => return Err(ApiError::Unsupported("Gab", "bookmarks")),
+            Platform::Minds => return Err(ApiError::Unsupported("Minds", "bookmarks")),
+        }
 
-                    matsu!(twitter.post(&message, &uploads, &flags))
-                }));
+        Ok(())
+    }
 
-                let gab = post_result(self.gab.as_ref().map(|gab| async move {
-                    let mut uploads = vec![];
-                    for image in images.iter() {
-                        let upload = matsu!(gab.upload_image(&image.name, &image.mime, &image.mmap[..]))?;
-                        uploads.push(upload);
-                    }
+    ///Removes post from bookmarks.
+    ///
+    ///Only Mastodon supports bookmarks.
+    pub async fn unbookmark(&self, post: &PostRef) -> Result<(), ApiError> {
+        match post.platform {
+            Platform::Mastodon => matsu!(self.mastodon()?.unbookmark(&post.id))?,
+            Platform::Twitter => return Err(ApiError::Unsupported("Twitter", "bookmarks")),
+            Platform::Gab => return Err(ApiError::Unsupported("Gab", "bookmarks")),
+            Platform::Minds => return Err(ApiError::Unsupported("Minds", "bookmarks")),
+        }
 
-                    matsu!(gab.post(&message, &uploads, &flags))
-                }));
+        Ok(())
+    }
+}
 
-                let mastodon = post_result(self.mastodon.as_ref().map(|mastodon| async move {
-                    let mut uploads = vec![];
-                    for image in images.iter() {
-                        let upload = matsu!(mastodon.upload_image(&image.name, &image.mime, &image.mmap[..]))?;
-                        uploads.push(upload);
-                    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-                    matsu!(mastodon.post(&message, &uploads, &flags))
-                }));
+    const PLATFORMS: [Platform; 4] = [Platform::Twitter, Platform::Gab, Platform::Mastodon, Platform::Minds];
 
-                let minds = post_result(self.minds.as_ref().map(|minds| async move {
-                    let image = unsafe { images.get_unchecked(0) };
-                    let upload = matsu!(minds.upload_image(&image.name, &image.mime, &image.mmap[..]))?;
+    ///Images with EXIF, that cannot be parsed to strip it.
+    fn corrupted_images() -> Vec<Image> {
+        //Length of APP1 segment exceeds content
+        let jpeg = b"\xff\xd8\xff\xe1\x01\x00Exif\x00\x00MM\x00\x2aGPS".to_vec();
+        //EXIF without VP8X chunk
+        let webp = b"RIFF\x18\x00\x00\x00WEBPEXIF\x04\x00\x00\x00GPS\x00VP8 \x00\x00\x00\x00".to_vec();
 
-                    matsu!(minds.post(&message, Some(upload), &flags))
-                }));
+        vec![Image::from_bytes("corrupted.jpg".to_owned(), jpeg), Image::from_bytes("corrupted.webp".to_owned(), webp)]
+    }
 
-                let res = futures_util::join!(twitter, gab, mastodon, minds);
-                res
+    #[test]
+    fn should_not_upload_corrupted_images_unchanged() {
+        let api = API::new(config::Settings::default());
+
+        for image in corrupted_images() {
+            assert_eq!(image.kind(), AttachmentKind::Image, "Kind of '{}'", image.name);
+            for &platform in PLATFORMS.iter() {
+                let images = core::slice::from_ref(&image);
+                match api.prepare_uploads(images, platform) {
+                    Err(ApiError::CannotStripMetadata(name)) => assert_eq!(name, image.name),
+                    Err(error) => panic!("Unexpected error for '{}' on {}: {}", image.name, platform, error),
+                    Ok(_) => panic!("'{}' is uploaded to {} with metadata", image.name, platform),
+                }
             }
-        };
+        }
+    }
 
-        Ok(PostResult {
-            inner,
-        })
+    #[test]
+    fn should_upload_corrupted_images_as_is_without_stripping() {
+        let api = API::new(config::Settings {
+            strip_metadata: false,
+            ..config::Settings::default()
+        });
+
+        for image in corrupted_images() {
+            let uploads = api.prepare_uploads(core::slice::from_ref(&image), Platform::Mastodon).expect("To prepare uploads");
+            assert_eq!(uploads.len(), 1);
+            assert_eq!(&uploads[0].data[..], image.data());
+        }
     }
 }
@@ -1,5 +1,6 @@
 //! Twitter's data primitives.
 use super::super::http::Method;
+use crate::data::AttachmentKind;
 use crate::config;
 
 use serde_derive::{Serialize, Deserialize};
@@ -159,27 +160,74 @@ impl Oauth {
     }
 }
 
-#[derive(Serialize, Debug)]
-///Media's payload
-pub struct Media {
-    ///Content
-    pub media_data: String,
+#[derive(Copy, Clone, Debug)]
+///Category of uploaded media.
+///
+///Determines how media is processed by server.
+pub enum MediaCategory {
+    ///Image to attach to tweet
+    TweetImage,
+    ///Animated GIF to attach to tweet
+    TweetGif,
+    ///Video to attach to tweet
+    TweetVideo,
+    ///Image to attach to direct message
+    DmImage,
+    ///Animated GIF to attach to direct message
+    DmGif,
+    ///Video to attach to direct message
+    DmVideo,
 }
 
-impl Media {
-    ///Creates BASE64 encoded media from file's content
-    pub fn from_bytes(bytes: &[u8]) -> Self {
-        use data_encoding::BASE64;
-        let media_data = BASE64.encode(bytes);
-        Self { media_data }
+impl MediaCategory {
+    ///Determines category of tweet's attachment
+    pub fn tweet(kind: AttachmentKind) -> Self {
+        match kind {
+            AttachmentKind::Gifv => MediaCategory::TweetGif,
+            AttachmentKind::Video => MediaCategory::TweetVideo,
+            _ => MediaCategory::TweetImage,
+        }
+    }
+
+    ///Determines category of direct message's attachment
+    pub fn direct(kind: AttachmentKind) -> Self {
+        match kind {
+            AttachmentKind::Gifv => MediaCategory::DmGif,
+            AttachmentKind::Video => MediaCategory::DmVideo,
+            _ => MediaCategory::DmImage,
+        }
+    }
+
+    ///Returns textual representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MediaCategory::TweetImage => "tweet_image",
+            MediaCategory::TweetGif => "tweet_gif",
+            MediaCategory::TweetVideo => "tweet_video",
+            MediaCategory::DmImage => "dm_image",
+            MediaCategory::DmGif => "dm_gif",
+            MediaCategory::DmVideo => "dm_video",
+        }
     }
 }
 
+#[derive(Deserialize, Debug)]
+///State of media's processing
+pub struct ProcessingInfo {
+    ///One of `pending`, `in_progress`, `failed` or `succeeded`
+    pub state: String,
+    ///Number of seconds to wait before checking status
+    #[serde(default)]
+    pub check_after_secs: u64,
+}
+
 #[derive(Deserialize, Debug)]
 ///Response with attachment's id
 pub struct MediaResponse {
     ///ID
     pub media_id: u64,
+    ///Present when media requires asynchronous processing
+    pub processing_info: Option<ProcessingInfo>,
 }
 
 #[derive(Serialize, Debug)]
@@ -225,3 +273,323 @@ pub struct TweetResponse {
     ///ID
     pub id: u64,
 }
+
+#[derive(Deserialize, Debug)]
+///Wrapper of API v2 responses.
+pub struct Data<T> {
+    ///Requested object
+    pub data: T,
+}
+
+#[derive(Serialize, Debug)]
+///Attachments of API v2 tweet.
+pub struct TweetMedia {
+    ///IDs of uploaded media
+    pub media_ids: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+///API v2 tweet's representation
+pub struct TweetV2<'a> {
+    ///Text of tweet
+    pub text: &'a str,
+    ///Attachments, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media: Option<TweetMedia>,
+}
+
+impl<'a> TweetV2<'a> {
+    ///Creates new instance
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            media: None,
+        }
+    }
+
+    ///Adds attachments
+    pub fn media_ids(mut self, ids: &[u64]) -> Self {
+        if !ids.is_empty() {
+            let media_ids = ids.iter().map(|id| id.to_string()).collect();
+            self.media = Some(TweetMedia { media_ids });
+        }
+        self
+    }
+}
+
+#[derive(Deserialize, Debug)]
+///API v2 response to successful tweet creation.
+pub struct TweetV2Response {
+    ///ID as string
+    pub id: String,
+}
+
+#[derive(Deserialize, Debug)]
+///API v2 response with attachment's id
+pub struct MediaV2Response {
+    ///ID as string
+    pub id: String,
+    ///Present when media requires asynchronous processing
+    pub processing_info: Option<ProcessingInfo>,
+}
+
+impl MediaV2Response {
+    ///Converts into the same response as API v1.1 has.
+    pub fn into_media_response(self) -> Option<MediaResponse> {
+        Some(MediaResponse {
+            media_id: self.id.parse().ok()?,
+            processing_info: self.processing_info,
+        })
+    }
+}
+
+#[derive(Deserialize, Debug)]
+///API v2 user's account information.
+pub struct UserV2 {
+    ///ID as string
+    pub id: String,
+    ///Handle of user
+    pub username: String,
+    ///Display name
+    pub name: String,
+}
+
+///Proof Key for Code Exchange (PKCE) of OAuth 2.0 authorization.
+pub struct Pkce {
+    ///Random secret, which is sent when exchanging code for token.
+    pub verifier: String,
+    ///Base64 encoded SHA-256 of `verifier`, which is sent with authorization request.
+    pub challenge: String,
+}
+
+impl Pkce {
+    ///Generates new verifier and its challenge.
+    pub fn new() -> Self {
+        use rand::{distributions, thread_rng, Rng};
+        use data_encoding::BASE64URL_NOPAD;
+
+        let verifier: String = thread_rng().sample_iter(&distributions::Alphanumeric).take(64).collect();
+        let challenge = ring::digest::digest(&ring::digest::SHA256, verifier.as_bytes());
+        let challenge = BASE64URL_NOPAD.encode(challenge.as_ref());
+
+        Self {
+            verifier,
+            challenge,
+        }
+    }
+}
+
+impl Default for Pkce {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Serialize, Debug)]
+///Request to exchange authorization code for OAuth 2.0 tokens.
+pub struct AuthorizationCode<'a> {
+    grant_type: &'static str,
+    code: &'a str,
+    redirect_uri: &'a str,
+    code_verifier: &'a str,
+    client_id: &'a str,
+}
+
+impl<'a> AuthorizationCode<'a> {
+    ///Creates new instance
+    pub fn new(client_id: &'a str, code: &'a str, redirect_uri: &'a str, code_verifier: &'a str) -> Self {
+        Self {
+            grant_type: "authorization_code",
+            code,
+            redirect_uri,
+            code_verifier,
+            client_id,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+///Request to obtain new OAuth 2.0 tokens, using refresh token.
+pub struct RefreshToken<'a> {
+    grant_type: &'static str,
+    refresh_token: &'a str,
+    client_id: &'a str,
+}
+
+impl<'a> RefreshToken<'a> {
+    ///Creates new instance
+    pub fn new(client_id: &'a str, refresh_token: &'a str) -> Self {
+        Self {
+            grant_type: "refresh_token",
+            refresh_token,
+            client_id,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+///Response with OAuth 2.0 tokens.
+pub struct Oauth2Response {
+    ///Access token to use with API
+    pub access_token: String,
+    ///Present only if `offline.access` scope is granted
+    #[serde(default)]
+    pub refresh_token: String,
+    ///Number of seconds until access token expires
+    #[serde(default)]
+    pub expires_in: u64,
+}
+
+#[derive(Deserialize, Debug)]
+///User's account information.
+pub struct User {
+    ///ID as string
+    pub id_str: String,
+    ///Handle of user
+    pub screen_name: String,
+    ///Display name
+    pub name: String,
+}
+
+#[derive(Deserialize, Debug)]
+///Media attached to tweet.
+pub struct MediaEntity {
+    ///HTTPS URL of media
+    pub media_url_https: String,
+}
+
+#[derive(Deserialize, Debug, Default)]
+///Extended entities of tweet.
+pub struct Entities {
+    ///Attached media
+    #[serde(default)]
+    pub media: Vec<MediaEntity>,
+}
+
+#[derive(Deserialize, Debug)]
+///Tweet from timeline.
+///
+///Requires `tweet_mode=extended`
+pub struct TimelineTweet {
+    ///ID
+    pub id: u64,
+    ///Untruncated text
+    pub full_text: String,
+    ///Time of creation in format `Wed Oct 10 20:19:24 +0000 2018`
+    pub created_at: String,
+    ///Author
+    pub user: User,
+    ///Attachments
+    #[serde(default)]
+    pub extended_entities: Entities,
+}
+
+impl TimelineTweet {
+    ///Converts into generic status.
+    ///
+    ///Returns `None` if creation time is invalid.
+    pub fn into_status(self) -> Option<crate::data::Status> {
+        let created_at = time::OffsetDateTime::parse(&self.created_at, "%a %b %d %H:%M:%S %z %Y").ok()?;
+
+        Some(crate::data::Status {
+            platform: crate::data::Platform::Twitter,
+            id: self.id.into(),
+            author: crate::data::Account {
+                id: self.user.id_str,
+                username: self.user.screen_name,
+                name: self.user.name,
+            },
+            text: crate::data::html_to_text(&self.full_text),
+            created_at,
+            media: self.extended_entities.media.into_iter().map(|media| media.media_url_https).collect(),
+        })
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct Recipient<'a> {
+    recipient_id: &'a str,
+}
+
+#[derive(Serialize, Debug)]
+struct MediaRef {
+    id: String,
+}
+
+#[derive(Serialize, Debug)]
+struct MessageAttachment {
+    #[serde(rename = "type")]
+    typ: &'static str,
+    media: MediaRef,
+}
+
+#[derive(Serialize, Debug)]
+struct MessageData<'a> {
+    text: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attachment: Option<MessageAttachment>,
+}
+
+#[derive(Serialize, Debug)]
+struct MessageCreate<'a> {
+    target: Recipient<'a>,
+    message_data: MessageData<'a>,
+}
+
+#[derive(Serialize, Debug)]
+struct MessageEvent<'a> {
+    #[serde(rename = "type")]
+    typ: &'static str,
+    message_create: MessageCreate<'a>,
+}
+
+#[derive(Serialize, Debug)]
+///Direct message's representation
+pub struct DirectMessage<'a> {
+    event: MessageEvent<'a>,
+}
+
+impl<'a> DirectMessage<'a> {
+    ///Creates new instance
+    pub fn new(recipient_id: &'a str, text: &'a str) -> Self {
+        Self {
+            event: MessageEvent {
+                typ: "message_create",
+                message_create: MessageCreate {
+                    target: Recipient {
+                        recipient_id,
+                    },
+                    message_data: MessageData {
+                        text,
+                        attachment: None,
+                    }
+                }
+            }
+        }
+    }
+
+    ///Adds attachment.
+    pub fn media_id(mut self, id: Option<u64>) -> Self {
+        self.event.message_create.message_data.attachment = id.map(|id| MessageAttachment {
+            typ: "media",
+            media: MediaRef {
+                id: id.to_string(),
+            }
+        });
+        self
+    }
+}
+
+#[derive(Deserialize, Debug)]
+///Created event
+pub struct EventId {
+    ///ID
+    pub id: String,
+}
+
+#[derive(Deserialize, Debug)]
+///Response to successful direct message creation.
+pub struct DirectMessageResponse {
+    ///Created event
+    pub event: EventId,
+}
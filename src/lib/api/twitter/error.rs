@@ -19,6 +19,10 @@ pub enum TwitterError {
     ///
     ///Should contain `id`
     ImageUploadInvalidResponse,
+    ///Server failed to process uploaded media.
+    MediaProcessingFailed,
+    ///Server didn't finish processing of uploaded media in time.
+    MediaProcessingTimeout,
     ///Failed to send request to perform text post.
     PostUploadSendError,
     ///Server rejected posting.
@@ -27,7 +31,46 @@ pub enum TwitterError {
     ///
     ///Should contain `id`
     PostUploadInvalidResponse,
-
+    ///Failed to send request to verify credentials.
+    VerifyCredentialsSendError,
+    ///Server rejected credentials.
+    VerifyCredentialsServerReject,
+    ///Server responded with invalid data.
+    ///
+    ///Should contain account's information
+    VerifyCredentialsInvalidResponse,
+    ///Failed to send request to fetch timeline.
+    TimelineSendError,
+    ///Server rejected timeline request.
+    TimelineServerReject,
+    ///Server responded with invalid data.
+    ///
+    ///Should contain list of posts
+    TimelineInvalidResponse,
+    ///Failed to send request to favourite or unfavourite post.
+    FavouriteSendError,
+    ///Server rejected to favourite or unfavourite post.
+    FavouriteServerReject,
+    ///Unable to find recipient of direct message.
+    RecipientNotFound,
+    ///Failed to send request to send direct message.
+    DirectMessageSendError,
+    ///Server rejected direct message.
+    DirectMessageServerReject,
+    ///Server responded with invalid data.
+    ///
+    ///Should contain event's `id`
+    DirectMessageInvalidResponse,
+    ///Failed to send request to obtain OAuth 2.0 tokens.
+    TokenSendError,
+    ///Server rejected to issue OAuth 2.0 tokens.
+    TokenServerReject,
+    ///Server responded with invalid data.
+    ///
+    ///Should contain `access_token`
+    TokenInvalidResponse,
+    ///Operation is not available in configured API version.
+    UnsupportedByVersion,
 }
 
 impl fmt::Display for TwitterError {
@@ -43,9 +86,27 @@ impl Error for TwitterError {
             &TwitterError::ImageUploadSendError => "Failed to send request to upload image",
             &TwitterError::ImageUploadServerReject => "Server rejected upload of image",
             &TwitterError::ImageUploadInvalidResponse => "Server sent invalid response. Doesn't contain field id",
+            &TwitterError::MediaProcessingFailed => "Server failed to process uploaded media",
+            &TwitterError::MediaProcessingTimeout => "Server didn't finish processing of uploaded media in time",
             &TwitterError::PostUploadSendError => "Failed to send request to perform text post",
             &TwitterError::PostUploadServerReject => "Server rejected posting",
             &TwitterError::PostUploadInvalidResponse => "Server sent invalid response. Doesn't contain field id",
+            &TwitterError::VerifyCredentialsSendError => "Failed to send request to verify credentials",
+            &TwitterError::VerifyCredentialsServerReject => "Server rejected credentials",
+            &TwitterError::VerifyCredentialsInvalidResponse => "Server sent invalid response. Doesn't contain account information",
+            &TwitterError::TimelineSendError => "Failed to send request to fetch timeline",
+            &TwitterError::TimelineServerReject => "Server rejected timeline request",
+            &TwitterError::TimelineInvalidResponse => "Server sent invalid response. Doesn't contain list of posts",
+            &TwitterError::FavouriteSendError => "Failed to send request to favourite post",
+            &TwitterError::FavouriteServerReject => "Server rejected to favourite post",
+            &TwitterError::RecipientNotFound => "Unable to find recipient of direct message",
+            &TwitterError::DirectMessageSendError => "Failed to send request to send direct message",
+            &TwitterError::DirectMessageServerReject => "Server rejected direct message",
+            &TwitterError::DirectMessageInvalidResponse => "Server sent invalid response. Doesn't contain field id",
+            &TwitterError::TokenSendError => "Failed to send request to obtain OAuth 2.0 tokens",
+            &TwitterError::TokenServerReject => "Server rejected to issue OAuth 2.0 tokens. Authorize again",
+            &TwitterError::TokenInvalidResponse => "Server sent invalid response. Doesn't contain access token",
+            &TwitterError::UnsupportedByVersion => "Operation is not supported by configured version of Twitter API",
         }
     }
 }
@@ -5,49 +5,205 @@ mod error;
 
 use std::collections::HashMap;
 
-use crate::config;
-use super::http::{self, GlobalRequest, Mime, Request, matsu};
+use crate::config::{self, OAuth2Token, TwitterVersion};
+use super::http::{self, multipart, GlobalRequest, Mime, Request, matsu};
 
-use crate::data::PostFlags;
+use core::time::Duration;
+
+use crate::data::{Page, PostFlags, PostId, Timeline};
 pub use error::TwitterError;
 
-const IMAGES_URL: &'static str = "https://upload.twitter.com/1.1/media/upload.json";
-const POST_URL: &'static str = "https://api.twitter.com/1.1/statuses/update.json";
+const IMAGES_URL: &str = "https://upload.twitter.com/1.1/media/upload.json";
+const IMAGES_V2_URL: &str = "https://api.twitter.com/2/media/upload";
+const POST_V2_URL: &str = "https://api.twitter.com/2/tweets";
+const USERS_ME_V2_URL: &str = "https://api.twitter.com/2/users/me";
+const OAUTH2_AUTHORIZE_URL: &str = "https://twitter.com/i/oauth2/authorize";
+const OAUTH2_TOKEN_URL: &str = "https://api.twitter.com/2/oauth2/token";
+const POST_URL: &str = "https://api.twitter.com/1.1/statuses/update.json";
+const HOME_TIMELINE_URL: &str = "https://api.twitter.com/1.1/statuses/home_timeline.json";
+const MENTIONS_URL: &str = "https://api.twitter.com/1.1/statuses/mentions_timeline.json";
+const FAVOURITE_URL: &str = "https://api.twitter.com/1.1/favorites/create.json";
+const UNFAVOURITE_URL: &str = "https://api.twitter.com/1.1/favorites/destroy.json";
+const USERS_SHOW_URL: &str = "https://api.twitter.com/1.1/users/show.json";
+const DIRECT_MESSAGE_URL: &str = "https://api.twitter.com/1.1/direct_messages/events/new.json";
+const VERIFY_CREDENTIALS_URL: &str = "https://api.twitter.com/1.1/account/verify_credentials.json";
+
+///Size of single APPEND chunk.
+///
+///Twitter accepts chunks up to 5MB, but the limit applies to whole multipart request,
+///so 4MiB leaves room for form boundaries and fields.
+const UPLOAD_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+///OAuth 2.0 scopes, required by API v2.
+///
+///`offline.access` is needed to obtain refresh token.
+pub const OAUTH2_SCOPES: &str = "tweet.read tweet.write users.read media.write offline.access";
+
+enum Auth {
+    V1(data::Oauth),
+    V2(OAuth2Token),
+}
 
 ///Twitter API
+///
+///Uses either API v1.1 or v2, depending on configuration.
+///API v2 supports only posting and verification of credentials.
 pub struct Twitter {
-    oauth: data::Oauth,
+    auth: Auth,
 }
 
 impl Twitter {
     ///Verifies and creates twitter API instance
-    pub fn new(config: config::Twitter) -> Result<Self, TwitterError> {
-        if config.consumer.key.len() == 0 || config.consumer.secret.len() == 0 || config.access.key.len() == 0 || config.access.secret.len() == 0 {
-            Err(TwitterError::InvalidAuthData)
-        } else {
-            let oauth = data::Oauth::new(config);
-            Ok(Self { oauth })
+    ///
+    ///For API v2 previously obtained token is re-used until it expires, and then refreshed.
+    pub async fn new(config: config::Twitter) -> Result<Self, TwitterError> {
+        match config.version {
+            TwitterVersion::V1 => if config.consumer.key.is_empty() || config.consumer.secret.is_empty() || config.access.key.is_empty() || config.access.secret.is_empty() {
+                Err(TwitterError::InvalidAuthData)
+            } else {
+                let oauth = data::Oauth::new(config);
+                Ok(Self { auth: Auth::V1(oauth) })
+            },
+            TwitterVersion::V2 => match config.token {
+                Some(token) => match token.is_expired() {
+                    false => Ok(Self { auth: Auth::V2(token) }),
+                    true if !token.refresh_token.is_empty() && !config.client_id.is_empty() => matsu!(Self::refresh(&config.client_id, &config.client_secret, &token.refresh_token)),
+                    true => Err(TwitterError::InvalidAuthData),
+                },
+                None => Err(TwitterError::InvalidAuthData),
+            },
+        }
+    }
+
+    ///Returns URL to authorize fie with API v2.
+    ///
+    ///Once authorized, Twitter redirects to `redirect_uri` with `code` and `state`.
+    pub fn authorize_url(client_id: &str, redirect_uri: &str, state: &str, pkce: &data::Pkce) -> String {
+        let params = [
+            ("response_type", "code"),
+            ("client_id", client_id),
+            ("redirect_uri", redirect_uri),
+            ("scope", OAUTH2_SCOPES),
+            ("state", state),
+            ("code_challenge", pkce.challenge.as_str()),
+            ("code_challenge_method", "S256"),
+        ];
+
+        let query = yukikaze::serde_urlencoded::to_string(params).expect("To serialize query");
+        format!("{}?{}", OAUTH2_AUTHORIZE_URL, query)
+    }
+
+    ///Exchanges authorization code for OAuth 2.0 tokens.
+    ///
+    ///`redirect_uri` must be the same as one used for authorization.
+    pub async fn login(client_id: &str, client_secret: &str, code: &str, redirect_uri: &str, pkce: &data::Pkce) -> Result<Self, TwitterError> {
+        let payload = data::AuthorizationCode::new(client_id, code, redirect_uri, &pkce.verifier);
+        matsu!(Self::request_token(client_id, client_secret, &payload))
+    }
+
+    ///Obtains new OAuth 2.0 tokens, using refresh token of previous authorization.
+    pub async fn refresh(client_id: &str, client_secret: &str, refresh_token: &str) -> Result<Self, TwitterError> {
+        let payload = data::RefreshToken::new(client_id, refresh_token);
+        matsu!(Self::request_token(client_id, client_secret, &payload))
+    }
+
+    async fn request_token<T: serde::Serialize>(client_id: &str, client_secret: &str, payload: &T) -> Result<Self, TwitterError> {
+        let mut req = Request::post(OAUTH2_TOKEN_URL).expect("To create request");
+        //Confidential clients must authenticate themselves.
+        if !client_secret.is_empty() {
+            req = req.basic_auth(client_id, Some(client_secret));
+        }
+
+        let req = req.form(payload)
+                     .expect("To serialize form params")
+                     .global()
+                     .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(TwitterError::TokenSendError),
+        }.map_err(|_| TwitterError::TokenSendError)?;
+
+        if !resp.is_success() {
+            return Err(TwitterError::TokenServerReject)
+        }
+
+        let oauth2 = match matsu!(resp.json::<data::Oauth2Response>()) {
+            Ok(oauth2) => oauth2,
+            Err(_) => return Err(TwitterError::TokenInvalidResponse),
+        };
+
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|now| now.as_secs()).unwrap_or(0);
+
+        Ok(Self {
+            auth: Auth::V2(OAuth2Token {
+                access_token: oauth2.access_token,
+                refresh_token: oauth2.refresh_token,
+                expires_at: now + oauth2.expires_in,
+            })
+        })
+    }
+
+    ///Returns version of API in use.
+    pub fn version(&self) -> TwitterVersion {
+        match self.auth {
+            Auth::V1(_) => TwitterVersion::V1,
+            Auth::V2(_) => TwitterVersion::V2,
+        }
+    }
+
+    ///Returns OAuth 2.0 tokens, that can be used next time.
+    ///
+    ///Available only with API v2.
+    pub fn token(&self) -> Option<&OAuth2Token> {
+        match self.auth {
+            Auth::V1(_) => None,
+            Auth::V2(ref token) => Some(token),
+        }
+    }
+
+    ///Returns OAuth 1.0a, that is required by API v1.1 only operations.
+    fn oauth(&self) -> Result<&data::Oauth, TwitterError> {
+        match self.auth {
+            Auth::V1(ref oauth) => Ok(oauth),
+            Auth::V2(_) => Err(TwitterError::UnsupportedByVersion),
+        }
+    }
+
+    ///Generates value of `Authorization` header.
+    fn authorization(&self, method: &http::Method, url: &str, params: HashMap<&str, &str>) -> String {
+        match self.auth {
+            Auth::V1(ref oauth) => oauth.gen_auth(method, url, params),
+            Auth::V2(ref token) => format!("Bearer {}", token.access_token),
+        }
+    }
+
+    fn media_url(&self) -> &'static str {
+        match self.auth {
+            Auth::V1(_) => IMAGES_URL,
+            Auth::V2(_) => IMAGES_V2_URL,
         }
     }
 
     ///Prepares image upload request.
     ///
+    ///Uses category of tweet's attachment.
     ///Result contains `id` from `MediaResponse`
-    pub async fn upload_image(&self, _name: &str, _mime: &Mime, data: &[u8]) -> Result<u64, TwitterError> {
-        let media = data::Media::from_bytes(data);
+    pub async fn upload_image(&self, _name: &str, mime: &Mime, data: &[u8]) -> Result<u64, TwitterError> {
+        let kind = crate::data::AttachmentKind::from_mime(mime, data);
+        matsu!(self.upload_media(mime, data, data::MediaCategory::tweet(kind)))
+    }
 
-        let auth_header = {
-            let mut auth_params = HashMap::new();
-            auth_params.insert("media_data", media.media_data.as_str());
-            self.oauth.gen_auth(&http::Method::POST, IMAGES_URL, auth_params)
-        };
+    async fn upload_command(&self, params: &HashMap<&str, &str>) -> Result<data::MediaResponse, TwitterError> {
+        let url = self.media_url();
+        let auth_header = self.authorization(&http::Method::POST, url, params.clone());
 
-        let req = Request::post(IMAGES_URL).expect("To create request")
-                                           .set_header(http::header::AUTHORIZATION, auth_header)
-                                           .form(&media)
-                                           .expect("To finalize request")
-                                           .global()
-                                           .send();
+        let req = Request::post(url).expect("To create request")
+                                    .set_header(http::header::AUTHORIZATION, auth_header)
+                                    .form(params)
+                                    .expect("To finalize request")
+                                    .global()
+                                    .send();
 
         let mut resp = match matsu!(req) {
             Ok(resp) => resp,
@@ -57,19 +213,141 @@ impl Twitter {
             }
         }.map_err(|_| TwitterError::ImageUploadSendError)?;
 
+        if !resp.is_success() {
+            return Err(TwitterError::ImageUploadServerReject)
+        }
+
+        let data = match self.auth {
+            Auth::V1(_) => matsu!(resp.json::<data::MediaResponse>()).ok(),
+            Auth::V2(_) => matsu!(resp.json::<data::Data<data::MediaV2Response>>()).ok().and_then(|media| media.data.into_media_response()),
+        };
+
+        data.ok_or(TwitterError::ImageUploadInvalidResponse)
+    }
+
+    async fn upload_append(&self, media_id: &str, segment_index: usize, chunk: &[u8]) -> Result<(), TwitterError> {
+        let mut form = multipart::Form::new();
+        form.add_field("command".to_string(), b"APPEND");
+        form.add_field("media_id".to_string(), media_id.as_bytes());
+        form.add_field("segment_index".to_string(), segment_index.to_string().as_bytes());
+        form.add_file_field("media".to_string(), "blob".to_string(), &yukikaze::mime::APPLICATION_OCTET_STREAM, chunk);
+
+        //Multipart body is not part of signature
+        let url = self.media_url();
+        let auth_header = self.authorization(&http::Method::POST, url, HashMap::new());
+
+        let req = Request::post(url).expect("To create request")
+                                    .set_header(http::header::AUTHORIZATION, auth_header)
+                                    .multipart(form)
+                                    .global()
+                                    .send();
+
+        let resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(err) => match matsu!(matsu!(err)) {
+                Ok(resp) => resp,
+                Err(_) => return Err(TwitterError::ImageUploadSendError)
+            }
+        }.map_err(|_| TwitterError::ImageUploadSendError)?;
+
+        match resp.is_success() {
+            true => Ok(()),
+            false => Err(TwitterError::ImageUploadServerReject),
+        }
+    }
+
+    async fn upload_status(&self, media_id: &str) -> Result<data::MediaResponse, TwitterError> {
+        let mut params = HashMap::new();
+        params.insert("command", "STATUS");
+        params.insert("media_id", media_id);
+
+        let url = self.media_url();
+        let auth_header = self.authorization(&http::Method::GET, url, params.clone());
+
+        let req = Request::get(url).expect("To create request")
+                                   .set_header(http::header::AUTHORIZATION, auth_header)
+                                   .query(&params)
+                                   .empty()
+                                   .global()
+                                   .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(TwitterError::ImageUploadSendError),
+        }.map_err(|_| TwitterError::ImageUploadSendError)?;
 
         if !resp.is_success() {
             return Err(TwitterError::ImageUploadServerReject)
         }
 
-        match matsu!(resp.json::<data::MediaResponse>()) {
-            Ok(data) => Ok(data.media_id),
-            Err(_) => Err(TwitterError::PostUploadInvalidResponse),
+        let data = match self.auth {
+            Auth::V1(_) => matsu!(resp.json::<data::MediaResponse>()).ok(),
+            Auth::V2(_) => matsu!(resp.json::<data::Data<data::MediaV2Response>>()).ok().and_then(|media| media.data.into_media_response()),
+        };
+
+        data.ok_or(TwitterError::ImageUploadInvalidResponse)
+    }
+
+    ///Prepares chunked media upload request.
+    ///
+    ///Media is sent by chunks, directly from `data`.
+    ///If server processes media asynchronously, waits until processing finishes.
+    ///
+    ///Result contains `id` from `MediaResponse`
+    pub async fn upload_media(&self, mime: &Mime, data: &[u8], category: data::MediaCategory) -> Result<u64, TwitterError> {
+        let total_bytes = data.len().to_string();
+        let media_type = mime.as_ref();
+
+        let mut params = HashMap::new();
+        params.insert("command", "INIT");
+        params.insert("total_bytes", total_bytes.as_str());
+        params.insert("media_type", media_type);
+        params.insert("media_category", category.as_str());
+
+        let media_id = matsu!(self.upload_command(&params))?.media_id.to_string();
+
+        for (segment_index, chunk) in data.chunks(UPLOAD_CHUNK_SIZE).enumerate() {
+            matsu!(self.upload_append(&media_id, segment_index, chunk))?;
+        }
+
+        let mut params = HashMap::new();
+        params.insert("command", "FINALIZE");
+        params.insert("media_id", media_id.as_str());
+
+        let mut response = matsu!(self.upload_command(&params))?;
+        let max_wait = http::get_media_timeout();
+        let mut waited = Duration::from_secs(0);
+
+        loop {
+            let check_after_secs = match response.processing_info {
+                None => break Ok(response.media_id),
+                Some(info) => match info.state.as_str() {
+                    "succeeded" => break Ok(response.media_id),
+                    "failed" => break Err(TwitterError::MediaProcessingFailed),
+                    _ => info.check_after_secs.max(1),
+                }
+            };
+
+            if waited >= max_wait {
+                break Err(TwitterError::MediaProcessingTimeout);
+            }
+
+            let check_after = Duration::from_secs(check_after_secs);
+            matsu!(http::delay(check_after));
+            waited += check_after;
+            response = matsu!(self.upload_status(&media_id))?;
         }
     }
 
     ///Prepares post upload request.
+    ///
+    ///API v2 has no way to mark tweet as sensitive, so `flags.nsfw` is ignored.
     pub async fn post(&self, message: &str, media_attachments: &[u64], flags: &PostFlags) -> Result<crate::data::PostId, TwitterError> {
+        let oauth = match self.auth {
+            Auth::V1(ref oauth) => oauth,
+            Auth::V2(_) => return matsu!(self.post_v2(message, media_attachments)),
+        };
+
         let tweet = data::Tweet::new(message).nsfw(flags.nsfw).media_ids(media_attachments);
 
         let auth_header = {
@@ -82,7 +360,7 @@ impl Twitter {
             if let Some(ids) = tweet.media_ids.as_ref() {
                 auth_params.insert("media_ids", ids);
             }
-            self.oauth.gen_auth(&http::Method::POST, POST_URL, auth_params)
+            oauth.gen_auth(&http::Method::POST, POST_URL, auth_params)
         };
 
         let req = Request::post(POST_URL).expect("To create request")
@@ -110,4 +388,263 @@ impl Twitter {
             Err(_) => Err(TwitterError::PostUploadInvalidResponse),
         }
     }
+
+    async fn post_v2(&self, message: &str, media_attachments: &[u64]) -> Result<crate::data::PostId, TwitterError> {
+        let tweet = data::TweetV2::new(message).media_ids(media_attachments);
+        let auth_header = self.authorization(&http::Method::POST, POST_V2_URL, HashMap::new());
+
+        let req = Request::post(POST_V2_URL).expect("To create request")
+                                            .set_header(http::header::AUTHORIZATION, auth_header)
+                                            .json(&tweet)
+                                            .expect("To create tweet data")
+                                            .global()
+                                            .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(err) => match matsu!(matsu!(err)) {
+                Ok(resp) => resp,
+                Err(_) => return Err(TwitterError::PostUploadSendError)
+            }
+        }.map_err(|_| TwitterError::PostUploadSendError)?;
+
+        if !resp.is_success() {
+            return Err(TwitterError::PostUploadServerReject)
+        }
+
+        match matsu!(resp.json::<data::Data<data::TweetV2Response>>()) {
+            Ok(tweet) => match tweet.data.id.parse::<u64>() {
+                Ok(id) => Ok(id.into()),
+                Err(_) => Err(TwitterError::PostUploadInvalidResponse),
+            },
+            Err(_) => Err(TwitterError::PostUploadInvalidResponse),
+        }
+    }
+
+    async fn verify_credentials_v2(&self) -> Result<crate::data::Account, TwitterError> {
+        let auth_header = self.authorization(&http::Method::GET, USERS_ME_V2_URL, HashMap::new());
+
+        let req = Request::get(USERS_ME_V2_URL).expect("To create request")
+                                               .set_header(http::header::AUTHORIZATION, auth_header)
+                                               .empty()
+                                               .global()
+                                               .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(TwitterError::VerifyCredentialsSendError),
+        }.map_err(|_| TwitterError::VerifyCredentialsSendError)?;
+
+        if !resp.is_success() {
+            return Err(TwitterError::VerifyCredentialsServerReject)
+        }
+
+        match matsu!(resp.json::<data::Data<data::UserV2>>()) {
+            Ok(user) => Ok(crate::data::Account {
+                id: user.data.id,
+                username: user.data.username,
+                name: user.data.name,
+            }),
+            Err(_) => Err(TwitterError::VerifyCredentialsInvalidResponse),
+        }
+    }
+
+    ///Prepares credentials verification request.
+    pub async fn verify_credentials(&self) -> Result<crate::data::Account, TwitterError> {
+        let oauth = match self.auth {
+            Auth::V1(ref oauth) => oauth,
+            Auth::V2(_) => return matsu!(self.verify_credentials_v2()),
+        };
+
+        let auth_header = oauth.gen_auth(&http::Method::GET, VERIFY_CREDENTIALS_URL, HashMap::new());
+
+        let req = Request::get(VERIFY_CREDENTIALS_URL).expect("To create request")
+                                                      .set_header(http::header::AUTHORIZATION, auth_header)
+                                                      .empty()
+                                                      .global()
+                                                      .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(TwitterError::VerifyCredentialsSendError),
+        }.map_err(|_| TwitterError::VerifyCredentialsSendError)?;
+
+        if !resp.is_success() {
+            return Err(TwitterError::VerifyCredentialsServerReject)
+        }
+
+        match matsu!(resp.json::<data::User>()) {
+            Ok(user) => Ok(crate::data::Account {
+                id: user.id_str,
+                username: user.screen_name,
+                name: user.name,
+            }),
+            Err(_) => Err(TwitterError::VerifyCredentialsInvalidResponse),
+        }
+    }
+
+    async fn timeline(&self, url: &str, page: &Page) -> Result<Timeline, TwitterError> {
+        let limit = page.limit.map(|limit| limit.to_string());
+
+        let mut params = HashMap::new();
+        params.insert("tweet_mode", "extended");
+        if let Some(limit) = limit.as_ref() {
+            params.insert("count", limit.as_str());
+        }
+        if let Some(max_id) = page.max_id.as_ref() {
+            params.insert("max_id", max_id.as_str());
+        }
+        if let Some(since_id) = page.since_id.as_ref() {
+            params.insert("since_id", since_id.as_str());
+        }
+
+        let auth_header = self.oauth()?.gen_auth(&http::Method::GET, url, params.clone());
+
+        let req = Request::get(url).expect("To create request")
+                                   .set_header(http::header::AUTHORIZATION, auth_header)
+                                   .query(&params)
+                                   .empty()
+                                   .global()
+                                   .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(TwitterError::TimelineSendError),
+        }.map_err(|_| TwitterError::TimelineSendError)?;
+
+        if !resp.is_success() {
+            return Err(TwitterError::TimelineServerReject)
+        }
+
+        let tweets = match matsu!(resp.json::<Vec<data::TimelineTweet>>()) {
+            Ok(tweets) => tweets,
+            Err(_) => return Err(TwitterError::TimelineInvalidResponse),
+        };
+
+        //max_id is inclusive so we need to start right below oldest tweet.
+        //There is nothing below zero, so no next page then.
+        let next = tweets.iter().map(|tweet| tweet.id).min().and_then(|id| id.checked_sub(1)).map(|id| id.to_string());
+        let mut statuses = Vec::with_capacity(tweets.len());
+        for tweet in tweets {
+            match tweet.into_status() {
+                Some(status) => statuses.push(status),
+                None => return Err(TwitterError::TimelineInvalidResponse),
+            }
+        }
+
+        Ok(Timeline {
+            statuses,
+            next,
+        })
+    }
+
+    ///Prepares request to fetch home timeline.
+    pub async fn home_timeline(&self, page: &Page) -> Result<Timeline, TwitterError> {
+        matsu!(self.timeline(HOME_TIMELINE_URL, page))
+    }
+
+    ///Prepares request to fetch tweets that mention user.
+    pub async fn mentions(&self, page: &Page) -> Result<Timeline, TwitterError> {
+        matsu!(self.timeline(MENTIONS_URL, page))
+    }
+
+    async fn favourite_action(&self, url: &str, id: &PostId) -> Result<(), TwitterError> {
+        let id = id.to_string();
+
+        let mut params = HashMap::new();
+        params.insert("id", id.as_str());
+
+        let auth_header = self.oauth()?.gen_auth(&http::Method::POST, url, params.clone());
+
+        let req = Request::post(url).expect("To create request")
+                                    .set_header(http::header::AUTHORIZATION, auth_header)
+                                    .form(&params)
+                                    .expect("To serialize form params")
+                                    .global()
+                                    .send();
+
+        let resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(TwitterError::FavouriteSendError),
+        }.map_err(|_| TwitterError::FavouriteSendError)?;
+
+        match resp.is_success() {
+            true => Ok(()),
+            false => Err(TwitterError::FavouriteServerReject),
+        }
+    }
+
+    ///Prepares request to like tweet.
+    pub async fn favourite(&self, id: &PostId) -> Result<(), TwitterError> {
+        matsu!(self.favourite_action(FAVOURITE_URL, id))
+    }
+
+    ///Prepares request to remove like from tweet.
+    pub async fn unfavourite(&self, id: &PostId) -> Result<(), TwitterError> {
+        matsu!(self.favourite_action(UNFAVOURITE_URL, id))
+    }
+
+    ///Looks up user's ID by screen name.
+    async fn user_id(&self, screen_name: &str) -> Result<String, TwitterError> {
+        let mut params = HashMap::new();
+        params.insert("screen_name", screen_name.trim_start_matches('@'));
+
+        let auth_header = self.oauth()?.gen_auth(&http::Method::GET, USERS_SHOW_URL, params.clone());
+
+        let req = Request::get(USERS_SHOW_URL).expect("To create request")
+                                              .set_header(http::header::AUTHORIZATION, auth_header)
+                                              .query(&params)
+                                              .empty()
+                                              .global()
+                                              .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(_) => return Err(TwitterError::DirectMessageSendError),
+        }.map_err(|_| TwitterError::DirectMessageSendError)?;
+
+        if !resp.is_success() {
+            return Err(TwitterError::RecipientNotFound)
+        }
+
+        match matsu!(resp.json::<data::User>()) {
+            Ok(user) => Ok(user.id_str),
+            Err(_) => Err(TwitterError::RecipientNotFound),
+        }
+    }
+
+    ///Prepares direct message request.
+    ///
+    ///Direct message can have only single attachment.
+    pub async fn send_direct(&self, recipient: &str, message: &str, media_attachment: Option<u64>) -> Result<crate::data::PostId, TwitterError> {
+        let recipient_id = matsu!(self.user_id(recipient))?;
+        let message = data::DirectMessage::new(&recipient_id, message).media_id(media_attachment);
+
+        //JSON body is not part of signature
+        let auth_header = self.oauth()?.gen_auth(&http::Method::POST, DIRECT_MESSAGE_URL, HashMap::new());
+
+        let req = Request::post(DIRECT_MESSAGE_URL).expect("To create request")
+                                                   .set_header(http::header::AUTHORIZATION, auth_header)
+                                                   .json(&message)
+                                                   .expect("To serialize direct message")
+                                                   .global()
+                                                   .send();
+
+        let mut resp = match matsu!(req) {
+            Ok(resp) => resp,
+            Err(err) => match matsu!(matsu!(err)) {
+                Ok(resp) => resp,
+                Err(_) => return Err(TwitterError::DirectMessageSendError)
+            }
+        }.map_err(|_| TwitterError::DirectMessageSendError)?;
+
+        if !resp.is_success() {
+            return Err(TwitterError::DirectMessageServerReject)
+        }
+
+        match matsu!(resp.json::<data::DirectMessageResponse>()) {
+            Ok(data) => Ok(data.event.id.into()),
+            Err(_) => Err(TwitterError::DirectMessageInvalidResponse),
+        }
+    }
 }
@@ -1,6 +1,8 @@
 //! Configuration module
 use serde_derive::{Serialize, Deserialize};
 
+use std::collections::HashMap;
+
 ///Describes which social platforms are enabled
 ///
 ///By default, if all platforms are not specified, then all are enabled.
@@ -44,15 +46,50 @@ pub struct Token {
     ///Secret
     pub secret: String,
 }
+
+///Version of Twitter API.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TwitterVersion {
+    ///API v1.1, authorized via OAuth 1.0a.
+    #[serde(rename = "1.1")]
+    #[default]
+    V1,
+    ///API v2, authorized via OAuth 2.0 with PKCE.
+    #[serde(rename = "2")]
+    V2,
+}
+
 /// Twitter configuration
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct Twitter {
+    #[serde(default)]
+    ///Version of API to use.
+    pub version: TwitterVersion,
     #[serde(default)]
     ///Consumer tokens, belongs to app.
+    ///
+    ///Used by API v1.1.
     pub consumer: Token,
     #[serde(default)]
     ///Access tokens, granted per user.
+    ///
+    ///Used by API v1.1.
     pub access: Token,
+    #[serde(default)]
+    ///OAuth 2.0 client ID, belongs to app.
+    ///
+    ///Used by API v2.
+    pub client_id: String,
+    #[serde(default)]
+    ///OAuth 2.0 client secret, belongs to app.
+    ///
+    ///Used by API v2. Needed only by confidential clients.
+    pub client_secret: String,
+    ///OAuth 2.0 tokens, obtained via authorization.
+    ///
+    ///Used by API v2. Not part of configuration file, as tokens are replaced on each refresh.
+    #[serde(skip)]
+    pub token: Option<OAuth2Token>,
 }
 
 /// Gab configuration.
@@ -85,14 +122,102 @@ pub struct Minds {
     #[serde(default)]
     pub username: String,
     ///Password for authorization
+    ///
+    ///Not needed, when `token` is available.
     #[serde(default)]
     pub password: String,
+    ///OAuth tokens, obtained via previous login.
+    ///
+    ///Not part of configuration file, as tokens are replaced on each login.
+    #[serde(skip)]
+    pub token: Option<OAuth2Token>,
+}
+
+///OAuth 2.0 tokens, obtained via authorization.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct OAuth2Token {
+    ///Access token to use with API.
+    pub access_token: String,
+    ///Token to obtain new access token without password.
+    pub refresh_token: String,
+    ///Unix timestamp, when access token expires.
+    ///
+    ///Zero if unknown, in which case access token is considered expired.
+    #[serde(default)]
+    pub expires_at: u64,
+}
+
+impl OAuth2Token {
+    ///Returns whether access token is expired or about to expire.
+    pub fn is_expired(&self) -> bool {
+        //Leave time to perform requests with the token.
+        const MARGIN: u64 = 60;
+
+        //Clock before epoch is broken, so token cannot be trusted either.
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|now| now.as_secs()).unwrap_or(u64::MAX);
+        self.expires_at <= now.saturating_add(MARGIN)
+    }
+}
+
+/// Recipient of direct messages.
+///
+/// Contains recipient's handle on each social media.
+/// Missing handle means that social media is not used.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct Recipient {
+    ///Twitter's screen name
+    #[serde(default)]
+    pub twitter: Option<String>,
+    ///Gab's username
+    #[serde(default)]
+    pub gab: Option<String>,
+    ///Mastodon's account in format `username@host`
+    #[serde(default)]
+    pub mastodon: Option<String>,
+    ///Minds's username
+    #[serde(default)]
+    pub minds: Option<String>,
+}
+
+impl Recipient {
+    ///Creates recipient with the same handle on each social media.
+    pub fn new(handle: &str) -> Self {
+        Self {
+            twitter: Some(handle.to_owned()),
+            gab: Some(handle.to_owned()),
+            mastodon: Some(handle.to_owned()),
+            minds: Some(handle.to_owned()),
+        }
+    }
+}
+
+/// Action to take when attachments exceed restrictions of social media.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AttachmentPolicy {
+    /// Refuses to post, before anything is uploaded.
+    #[default]
+    Fail,
+    /// Drops attachments that do not fit, keeping the first ones.
+    Trim,
 }
 
 fn default_timeout() -> u64 {
     5
 }
 
+fn default_media_timeout() -> u64 {
+    120
+}
+
+fn default_upload_concurrency() -> usize {
+    4
+}
+
+fn default_true() -> bool {
+    true
+}
+
 /// Fie's settings
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Settings {
@@ -101,11 +226,54 @@ pub struct Settings {
     ///
     /// By default is 5.
     pub timeout: u64,
+    #[serde(default = "default_media_timeout")]
+    /// Amount of seconds to wait for server to process uploaded media.
+    ///
+    /// Applies to media that is processed asynchronously, like video.
+    ///
+    /// By default is 120.
+    pub media_timeout: u64,
+    #[serde(default = "default_upload_concurrency")]
+    /// Maximum number of attachments to upload at the same time on each social media.
+    ///
+    /// By default is 4.
+    pub upload_concurrency: usize,
+    #[serde(default = "default_true")]
+    /// Whether to remove metadata (EXIF, XMP and etc) from images before upload.
+    ///
+    /// Applies to JPEG, PNG and WebP images.
+    /// Malformed image, which metadata cannot be stripped, is refused instead of being uploaded as it is.
+    ///
+    /// By default is true.
+    pub strip_metadata: bool,
+    #[serde(default = "default_true")]
+    /// Whether to keep EXIF orientation when removing metadata.
+    ///
+    /// By default is true.
+    pub keep_orientation: bool,
+    #[serde(default = "default_true")]
+    /// Whether to keep ICC color profile when removing metadata.
+    ///
+    /// By default is true.
+    pub keep_icc_profile: bool,
+    #[serde(default)]
+    /// What to do with attachments, that exceed restrictions of social media.
+    ///
+    /// By default is `fail`.
+    pub attachment_policy: AttachmentPolicy,
 }
 
 impl Default for Settings {
     fn default() -> Self {
-        Self { timeout: 5 }
+        Self {
+            timeout: 5,
+            media_timeout: 120,
+            upload_concurrency: 4,
+            strip_metadata: true,
+            keep_orientation: true,
+            keep_icc_profile: true,
+            attachment_policy: AttachmentPolicy::Fail,
+        }
     }
 }
 
@@ -133,4 +301,7 @@ pub struct Config {
     ///Fie settings
     #[serde(default)]
     pub settings: Settings,
+    ///Address book of direct messages' recipients.
+    #[serde(default)]
+    pub recipients: HashMap<String, Recipient>,
 }
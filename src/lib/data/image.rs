@@ -1,35 +1,782 @@
 //!Image utilities
 
 use memmap::{Mmap, MmapOptions};
-use mime_guess::{Mime};
+use mime_guess::{mime, Mime};
+use serde_derive::{Deserialize};
 
+use core::fmt;
 use std::fs::File;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+///Kind of attachment
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AttachmentKind {
+    ///Static image
+    Image,
+    ///Animated image, that is usually converted into looping video
+    Gifv,
+    ///Video
+    Video,
+    ///Audio
+    Audio,
+    ///Anything else
+    #[serde(other)]
+    Unknown,
+}
+
+impl AttachmentKind {
+    ///Determines kind from MIME and content.
+    ///
+    ///GIF is considered animated only if it has more than one frame.
+    pub fn from_mime(mime: &Mime, data: &[u8]) -> Self {
+        match (mime.type_(), mime.subtype()) {
+            (mime::IMAGE, mime::GIF) if gif_frames(data) > 1 => AttachmentKind::Gifv,
+            (mime::IMAGE, _) => AttachmentKind::Image,
+            (mime::VIDEO, _) => AttachmentKind::Video,
+            (mime::AUDIO, _) => AttachmentKind::Audio,
+            _ => AttachmentKind::Unknown,
+        }
+    }
+}
+
+///Major brands of `ftyp` box, that are recognized as MP4 video.
+const MP4_BRANDS: &[[u8; 4]] = &[*b"isom", *b"iso2", *b"mp41", *b"mp42", *b"avc1", *b"M4V "];
+///Valid sizes of BMP's DIB header, from `BITMAPCOREHEADER` to `BITMAPV5HEADER`.
+const BMP_DIB_SIZES: &[u32] = &[12, 40, 52, 56, 64, 108, 124];
+
+fn read_u32_le(data: &[u8], pos: usize) -> Option<u32> {
+    let mut bytes = [0u8; 4];
+    bytes.copy_from_slice(data.get(pos..pos + 4)?);
+    Some(u32::from_le_bytes(bytes))
+}
+
+///Checks BMP's file header and size of DIB header.
+fn is_bmp(data: &[u8]) -> bool {
+    //File header is 14 bytes, followed by DIB header's size.
+    let (file_size, offset, dib_size) = match (read_u32_le(data, 2), read_u32_le(data, 10), read_u32_le(data, 14)) {
+        (Some(file_size), Some(offset), Some(dib_size)) => (file_size, offset, dib_size),
+        _ => return false,
+    };
+
+    BMP_DIB_SIZES.contains(&dib_size) && offset >= 14 + dib_size && file_size >= offset
+}
+
+///Skips GIF's data sub-blocks, returning position after terminator.
+fn skip_gif_blocks(data: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        match *data.get(pos)? {
+            0 => return Some(pos + 1),
+            len => pos += 1 + len as usize,
+        }
+    }
+}
+
+///Counts frames of GIF image.
+///
+///Truncated or damaged data is counted up to the first invalid block.
+fn gif_frames(data: &[u8]) -> usize {
+    //Header and logical screen descriptor
+    let mut pos = 13;
+    let flags = match data.get(10) {
+        Some(flags) => *flags,
+        None => return 0,
+    };
+    if flags & 0x80 != 0 {
+        pos += 3 * (2 << (flags & 0x07));
+    }
+
+    let mut frames = 0;
+    loop {
+        pos = match data.get(pos) {
+            //Extension: label followed by sub-blocks
+            Some(0x21) => match skip_gif_blocks(data, pos + 2) {
+                Some(pos) => pos,
+                None => break,
+            },
+            //Image descriptor: 9 bytes, optional local color table, LZW code size and sub-blocks
+            Some(0x2C) => {
+                let flags = match data.get(pos + 9) {
+                    Some(flags) => *flags,
+                    None => break,
+                };
+                let mut next = pos + 10;
+                if flags & 0x80 != 0 {
+                    next += 3 * (2 << (flags & 0x07));
+                }
+                frames += 1;
+                match skip_gif_blocks(data, next + 1) {
+                    Some(pos) => pos,
+                    None => break,
+                }
+            },
+            _ => break,
+        };
+    }
+
+    frames
+}
+
+///Detects MIME by looking at signature in the beginning of content.
+///
+///Returns `None` if content is not recognized.
+pub fn sniff_mime(data: &[u8]) -> Option<Mime> {
+    let mime = match data {
+        [0xFF, 0xD8, 0xFF, ..] => "image/jpeg",
+        [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => "image/png",
+        [b'G', b'I', b'F', b'8', b'7', b'a', ..] | [b'G', b'I', b'F', b'8', b'9', b'a', ..] => "image/gif",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => "image/webp",
+        [b'B', b'M', ..] if is_bmp(data) => "image/bmp",
+        [b'I', b'I', 0x2A, 0x00, ..] | [b'M', b'M', 0x00, 0x2A, ..] => "image/tiff",
+        [_, _, _, _, b'f', b't', b'y', b'p', b'a', b'v', b'i', b'f', ..] => "image/avif",
+        [_, _, _, _, b'f', b't', b'y', b'p', b'h', b'e', b'i', b'c', ..] => "image/heic",
+        [_, _, _, _, b'f', b't', b'y', b'p', b'q', b't', b' ', b' ', ..] => "video/quicktime",
+        [_, _, _, _, b'f', b't', b'y', b'p', b'M', b'4', b'A', b' ', ..] => "audio/mp4",
+        [_, _, _, _, b'f', b't', b'y', b'p', a, b, c, d, ..] if MP4_BRANDS.contains(&[*a, *b, *c, *d]) => "video/mp4",
+        [0x1A, 0x45, 0xDF, 0xA3, ..] => "video/webm",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => "audio/wav",
+        [b'O', b'g', b'g', b'S', ..] => "audio/ogg",
+        [b'f', b'L', b'a', b'C', ..] => "audio/flac",
+        [b'I', b'D', b'3', ..] | [0xFF, 0xFB, ..] | [0xFF, 0xF3, ..] | [0xFF, 0xF2, ..] => "audio/mpeg",
+        _ => return None,
+    };
+
+    Some(mime.parse().expect("To parse valid MIME"))
+}
+
+///Focal point of image, used to crop thumbnails.
+///
+///Coordinates are in range `-1.0..=1.0`, with `(0, 0)` being center of image.
+///`x` grows to the right and `y` grows upwards.
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq)]
+pub struct Focus {
+    ///Horizontal coordinate
+    pub x: f32,
+    ///Vertical coordinate
+    pub y: f32,
+}
+
+impl Focus {
+    ///Creates new focal point, clamping coordinates into valid range.
+    pub fn new(x: f32, y: f32) -> Self {
+        Self {
+            x: x.clamp(-1.0, 1.0),
+            y: y.clamp(-1.0, 1.0),
+        }
+    }
+}
+
+impl fmt::Display for Focus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.2},{:.2}", self.x, self.y)
+    }
+}
+
+///Source of attachment's content.
+pub enum AttachmentSource {
+    ///File on disk.
+    File(PathBuf),
+    ///Content in memory.
+    Bytes {
+        ///Name of attachment, used as file name on upload.
+        name: String,
+        ///Content
+        data: Vec<u8>,
+    },
+    ///Content that is read fully when attachment is loaded.
+    Reader {
+        ///Name of attachment, used as file name on upload.
+        name: String,
+        ///Source of content
+        reader: Box<dyn io::Read + Send>,
+    },
+}
+
+impl fmt::Debug for AttachmentSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AttachmentSource::File(path) => f.debug_tuple("File").field(path).finish(),
+            AttachmentSource::Bytes { name, data } => f.debug_struct("Bytes").field("name", name).field("len", &data.len()).finish(),
+            AttachmentSource::Reader { name, .. } => f.debug_struct("Reader").field("name", name).finish(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AttachmentDef {
+    Path(PathBuf),
+    Full {
+        path: PathBuf,
+        #[serde(default)]
+        focus: Option<Focus>,
+        #[serde(default)]
+        sensitive: bool,
+    },
+}
+
+impl From<AttachmentDef> for Attachment {
+    fn from(def: AttachmentDef) -> Self {
+        match def {
+            AttachmentDef::Path(path) => Attachment::file(path),
+            AttachmentDef::Full { path, focus, sensitive } => Attachment {
+                source: AttachmentSource::File(path),
+                focus,
+                sensitive,
+            },
+        }
+    }
+}
+
+///Attachment to post.
+///
+///Deserialized either from path to file or from table with `path`, and optional `focus` and `sensitive`.
+#[derive(Deserialize, Debug)]
+#[serde(from = "AttachmentDef")]
+pub struct Attachment {
+    ///Source of content.
+    pub source: AttachmentSource,
+    ///Focal point of image.
+    ///
+    ///Used only by Mastodon and Gab.
+    pub focus: Option<Focus>,
+    ///Whether attachment is sensitive.
+    ///
+    ///As no social media supports sensitive attachments, the whole post is marked as NSFW instead.
+    pub sensitive: bool,
+}
+
+impl Attachment {
+    fn new(source: AttachmentSource) -> Self {
+        Self {
+            source,
+            focus: None,
+            sensitive: false,
+        }
+    }
+
+    ///Creates attachment from file.
+    pub fn file<P: Into<PathBuf>>(path: P) -> Self {
+        Self::new(AttachmentSource::File(path.into()))
+    }
+
+    ///Creates attachment from content in memory.
+    pub fn bytes<N: Into<String>>(name: N, data: Vec<u8>) -> Self {
+        Self::new(AttachmentSource::Bytes {
+            name: name.into(),
+            data,
+        })
+    }
+
+    ///Creates attachment that reads its content from `reader`.
+    pub fn reader<N: Into<String>, R: io::Read + Send + 'static>(name: N, reader: R) -> Self {
+        Self::new(AttachmentSource::Reader {
+            name: name.into(),
+            reader: Box::new(reader),
+        })
+    }
+
+    ///Sets focal point of image.
+    pub fn focus(mut self, focus: Focus) -> Self {
+        self.focus = Some(focus);
+        self
+    }
+
+    ///Marks attachment as sensitive.
+    pub fn sensitive(mut self, sensitive: bool) -> Self {
+        self.sensitive = sensitive;
+        self
+    }
+
+    ///Returns name to identify attachment.
+    ///
+    ///For file it is its path.
+    pub fn name(&self) -> String {
+        match &self.source {
+            AttachmentSource::File(path) => path.display().to_string(),
+            AttachmentSource::Bytes { name, .. } => name.clone(),
+            AttachmentSource::Reader { name, .. } => name.clone(),
+        }
+    }
+
+    ///Loads attachment's content.
+    pub fn load(self) -> io::Result<Image> {
+        let Attachment { source, focus, sensitive } = self;
+
+        let mut image = match source {
+            AttachmentSource::File(path) => Image::open(path)?,
+            AttachmentSource::Bytes { name, data } => Image::from_bytes(name, data),
+            AttachmentSource::Reader { name, mut reader } => {
+                let mut data = Vec::new();
+                reader.read_to_end(&mut data)?;
+                Image::from_bytes(name, data)
+            },
+        };
+
+        image.focus = focus;
+        image.sensitive = sensitive;
+        Ok(image)
+    }
+}
+
+impl From<PathBuf> for Attachment {
+    fn from(path: PathBuf) -> Self {
+        Attachment::file(path)
+    }
+}
+
+impl From<String> for Attachment {
+    fn from(path: String) -> Self {
+        Attachment::file(path)
+    }
+}
+
+impl<'a> From<&'a str> for Attachment {
+    fn from(path: &'a str) -> Self {
+        Attachment::file(path)
+    }
+}
+
+enum Content {
+    Mapped {
+        _file: File,
+        mmap: Mmap,
+    },
+    Memory(Vec<u8>),
+}
 
 ///Loaded image.
 ///
-///Internally it uses `memmap`
+///File's content is accessed via `memmap`.
 pub struct Image {
     ///Image's file name
     pub name: String,
     ///Mime of Image
     pub mime: Mime,
-    _file: File,
-    pub(crate) mmap: Mmap,
+    ///Focal point of image.
+    pub focus: Option<Focus>,
+    ///Whether image is sensitive.
+    pub sensitive: bool,
+    content: Content,
 }
 
 impl Image {
     ///Opens image in specified file
     ///
-    ///Doesn't verify whether it is actually image or not.
+    ///MIME is detected from file's content, regardless of extension.
+    ///If content is not recognized, MIME is `application/octet-stream` and kind is `AttachmentKind::Unknown`.
     pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
-        let _file = File::open(&path)?;
-        let mmap = unsafe { MmapOptions::new().map(&_file)? };
+        let name = match path.as_ref().file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("'{}' is not a file", path.as_ref().display()))),
+        };
+
+        let file = File::open(&path)?;
+        let mmap = unsafe { MmapOptions::new().map(&file)? };
+
+        let mime = sniff_mime(&mmap[..]).unwrap_or(mime::APPLICATION_OCTET_STREAM);
+
+        Ok(Image { name, mime, focus: None, sensitive: false, content: Content::Mapped { _file: file, mmap } })
+    }
+
+    ///Creates image from content in memory.
+    ///
+    ///MIME is detected the same way as in `Image::open`.
+    pub fn from_bytes(name: String, data: Vec<u8>) -> Self {
+        let mime = sniff_mime(&data).unwrap_or(mime::APPLICATION_OCTET_STREAM);
+
+        Image { name, mime, focus: None, sensitive: false, content: Content::Memory(data) }
+    }
+
+    ///Returns image's content
+    pub fn data(&self) -> &[u8] {
+        match &self.content {
+            Content::Mapped { mmap, .. } => &mmap[..],
+            Content::Memory(data) => &data[..],
+        }
+    }
+
+    ///Returns kind of attachment
+    pub fn kind(&self) -> AttachmentKind {
+        AttachmentKind::from_mime(&self.mime, self.data())
+    }
+}
+
+///Limits imposed by social media on uploaded images.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ImageLimits {
+    ///Maximum size of image in bytes.
+    pub max_size: usize,
+    ///Maximum width and height of image in pixels.
+    pub max_dimension: u32,
+}
+
+impl ImageLimits {
+    ///Twitter's limits for static images.
+    pub const TWITTER: Self = ImageLimits { max_size: 5 * 1024 * 1024, max_dimension: 8192 };
+    ///Gab's limits for static images.
+    pub const GAB: Self = ImageLimits { max_size: 8 * 1024 * 1024, max_dimension: 4096 };
+    ///Mastodon's limits for static images, as configured by default.
+    pub const MASTODON: Self = ImageLimits { max_size: 8 * 1024 * 1024, max_dimension: 4096 };
+    ///Minds's limits for static images.
+    pub const MINDS: Self = ImageLimits { max_size: 10 * 1024 * 1024, max_dimension: 4096 };
+}
+
+///Restrictions of social media on attachments within single post.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Capabilities {
+    ///Maximum number of images.
+    pub max_images: usize,
+    ///Maximum number of videos, animated GIFs and audio.
+    pub max_videos: usize,
+    ///Whether audio can be attached.
+    ///
+    ///Audio counts towards `max_videos`.
+    pub audio: bool,
+    ///Whether images can be attached together with video, animated GIF or audio.
+    pub mixed_media: bool,
+}
+
+impl Capabilities {
+    ///Twitter's restrictions.
+    pub const TWITTER: Self = Capabilities { max_images: 4, max_videos: 1, audio: false, mixed_media: false };
+    ///Gab's restrictions.
+    ///
+    ///Its fork of Mastodon accepts only images and video.
+    pub const GAB: Self = Capabilities { max_images: 4, max_videos: 1, audio: false, mixed_media: false };
+    ///Mastodon's restrictions, as configured by default.
+    pub const MASTODON: Self = Capabilities { max_images: 4, max_videos: 1, audio: true, mixed_media: false };
+    ///Minds's restrictions.
+    ///
+    ///Images are attached to single activity, as many as its composer allows, while video is posted on its own.
+    pub const MINDS: Self = Capabilities { max_images: 4, max_videos: 1, audio: false, mixed_media: false };
+
+    ///Checks whether attachments of specified kinds can be posted together.
+    pub fn check<I: IntoIterator<Item=AttachmentKind>>(&self, kinds: I) -> Result<(), AttachmentViolation> {
+        let mut images = 0;
+        let mut videos = 0;
+
+        for kind in kinds {
+            match kind {
+                AttachmentKind::Image => images += 1,
+                AttachmentKind::Gifv | AttachmentKind::Video => videos += 1,
+                AttachmentKind::Audio if self.audio => videos += 1,
+                AttachmentKind::Audio | AttachmentKind::Unknown => return Err(AttachmentViolation::Unsupported(kind)),
+            }
+        }
+
+        if images > self.max_images {
+            Err(AttachmentViolation::TooManyImages(self.max_images))
+        } else if videos > self.max_videos {
+            Err(AttachmentViolation::TooManyVideos(self.max_videos))
+        } else if !self.mixed_media && images > 0 && videos > 0 {
+            Err(AttachmentViolation::MixedMedia)
+        } else {
+            Ok(())
+        }
+    }
+
+    ///Selects attachments that can be posted together.
+    ///
+    ///Attachments are taken in order, skipping those that do not fit.
+    pub fn trim<'a>(&self, attachments: &'a [Image]) -> Vec<&'a Image> {
+        let mut images = 0;
+        let mut videos = 0;
+
+        attachments.iter().filter(|attachment| match attachment.kind() {
+            AttachmentKind::Image if images < self.max_images && (self.mixed_media || videos == 0) => {
+                images += 1;
+                true
+            },
+            AttachmentKind::Image => false,
+            AttachmentKind::Audio if !self.audio => false,
+            AttachmentKind::Unknown => false,
+            _ if videos < self.max_videos && (self.mixed_media || images == 0) => {
+                videos += 1;
+                true
+            },
+            _ => false,
+        }).collect()
+    }
+}
+
+///Describes why attachments cannot be posted together.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AttachmentViolation {
+    ///Exceeded maximum number of images.
+    TooManyImages(usize),
+    ///Exceeded maximum number of videos, animated GIFs and audio.
+    TooManyVideos(usize),
+    ///Images are mixed with video, animated GIF or audio.
+    MixedMedia,
+    ///Kind of attachment is not accepted at all.
+    Unsupported(AttachmentKind),
+}
+
+impl fmt::Display for AttachmentViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AttachmentViolation::TooManyImages(max) => write!(f, "at most {} images are allowed", max),
+            AttachmentViolation::TooManyVideos(max) => write!(f, "at most {} video, animated GIF or audio is allowed", max),
+            AttachmentViolation::MixedMedia => f.write_str("images cannot be mixed with video, animated GIF or audio"),
+            AttachmentViolation::Unsupported(AttachmentKind::Audio) => f.write_str("audio is not supported"),
+            AttachmentViolation::Unsupported(_) => f.write_str("only images, video, animated GIFs and audio are supported"),
+        }
+    }
+}
+
+#[cfg(feature = "image-processing")]
+///Image that is re-encoded to fit limits.
+pub struct Processed {
+    ///MIME of new content.
+    pub mime: Mime,
+    ///Re-encoded content.
+    pub data: Vec<u8>,
+}
+
+#[cfg(feature = "image-processing")]
+impl Image {
+    ///Downscales and re-encodes image, if it exceeds `limits`.
+    ///
+    ///Only static JPEG, PNG, WebP, BMP and TIFF images are processed.
+    ///Images with transparency are encoded as PNG, the rest as JPEG.
+    ///Resulting image has no metadata, and EXIF orientation is applied to it.
+    ///ICC color profile is carried over, if `keep_icc_profile` is set.
+    ///
+    ///Returns `None` if image already fits, cannot be processed or cannot be squeezed into limits.
+    ///File itself is never modified.
+    pub fn fit(&self, limits: &ImageLimits, keep_icc_profile: bool) -> Option<Processed> {
+        use image::GenericImageView;
+        use image::imageops::FilterType;
+
+        const MAX_ATTEMPTS: usize = 8;
+        const MIN_JPEG_QUALITY: u8 = 50;
+
+        match self.mime.subtype().as_str() {
+            "jpeg" | "png" | "webp" | "bmp" | "tiff" => (),
+            _ => return None,
+        }
+
+        let data = self.data();
+        let (width, height) = image::io::Reader::new(io::Cursor::new(data)).with_guessed_format().ok()?.into_dimensions().ok()?;
+
+        if data.len() <= limits.max_size && width.max(height) <= limits.max_dimension {
+            return None;
+        }
+
+        let mut image = image::load_from_memory(data).ok()?;
+        //Re-encoded image has no EXIF, so orientation should be applied to pixels.
+        image = match super::metadata::orientation(data, &self.mime) {
+            Some(2) => image.fliph(),
+            Some(3) => image.rotate180(),
+            Some(4) => image.flipv(),
+            Some(5) => image.rotate90().fliph(),
+            Some(6) => image.rotate90(),
+            Some(7) => image.rotate270().fliph(),
+            Some(8) => image.rotate270(),
+            _ => image,
+        };
+
+        if width.max(height) > limits.max_dimension {
+            image = image.resize(limits.max_dimension, limits.max_dimension, FilterType::Lanczos3);
+        }
+
+        let profile = match keep_icc_profile {
+            true => super::metadata::icc_profile(data, &self.mime),
+            false => None,
+        };
+        let has_alpha = image.color().has_alpha();
+        let mut quality = 90;
+
+        for _ in 0..MAX_ATTEMPTS {
+            let mut data = Vec::new();
+            let mime = match has_alpha {
+                true => {
+                    image.write_to(&mut data, image::ImageOutputFormat::Png).ok()?;
+                    mime::IMAGE_PNG
+                },
+                false => {
+                    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut data, quality).encode_image(&image).ok()?;
+                    mime::IMAGE_JPEG
+                },
+            };
+
+            let data = match profile.as_ref().and_then(|profile| super::metadata::embed_icc_profile(&data, &mime, profile)) {
+                Some(data) => data,
+                None => data,
+            };
+
+            if data.len() <= limits.max_size {
+                return Some(Processed { mime, data });
+            }
+
+            //Lower quality first, and only then start to lose pixels.
+            if !has_alpha && quality > MIN_JPEG_QUALITY {
+                quality -= 10;
+            } else {
+                let (width, height) = image.dimensions();
+                image = image.resize(width * 3 / 4, height * 3 / 4, FilterType::Lanczos3);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Platform;
+
+    const GIF_HEADER: &[u8] = b"GIF89a\x01\x00\x01\x00\x80\x00\x00\x00\x00\x00\xff\xff\xff";
+    const GIF_CONTROL: &[u8] = b"\x21\xf9\x04\x00\x0a\x00\x00\x00";
+    const GIF_FRAME: &[u8] = b"\x2c\x00\x00\x00\x00\x01\x00\x01\x00\x00\x02\x02\x44\x01\x00";
+
+    fn gif(frames: usize) -> Vec<u8> {
+        let mut data = GIF_HEADER.to_vec();
+        for _ in 0..frames {
+            data.extend_from_slice(GIF_CONTROL);
+            data.extend_from_slice(GIF_FRAME);
+        }
+        data.push(0x3B);
+        data
+    }
+
+    fn bmp(offset: u32, dib_size: u32) -> Vec<u8> {
+        let mut data = b"BM".to_vec();
+        data.extend_from_slice(&(offset + 4).to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&offset.to_le_bytes());
+        data.extend_from_slice(&dib_size.to_le_bytes());
+        data
+    }
+
+    fn ftyp(brand: &[u8; 4]) -> Vec<u8> {
+        let mut data = b"\x00\x00\x00\x18ftyp".to_vec();
+        data.extend_from_slice(brand);
+        data
+    }
+
+    fn sniff(data: &[u8]) -> Option<String> {
+        sniff_mime(data).map(|mime| mime.to_string())
+    }
+
+    #[test]
+    fn should_sniff_images() {
+        assert_eq!(sniff(b"\xff\xd8\xff\xe0").as_deref(), Some("image/jpeg"));
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\n").as_deref(), Some("image/png"));
+        assert_eq!(sniff(&gif(1)).as_deref(), Some("image/gif"));
+        assert_eq!(sniff(b"RIFF\x00\x00\x00\x00WEBPVP8 ").as_deref(), Some("image/webp"));
+        assert_eq!(sniff(&bmp(54, 40)).as_deref(), Some("image/bmp"));
+        assert_eq!(sniff(&bmp(138, 124)).as_deref(), Some("image/bmp"));
+    }
+
+    #[test]
+    fn should_not_sniff_text_as_bmp() {
+        assert_eq!(sniff(b"BM"), None);
+        assert_eq!(sniff(b"BMW is a car manufacturer"), None);
+        assert_eq!(sniff(&bmp(54, 41)), None);
+        assert_eq!(sniff(&bmp(20, 40)), None);
+    }
+
+    #[test]
+    fn should_sniff_only_known_mp4_brands() {
+        for brand in MP4_BRANDS {
+            assert_eq!(sniff(&ftyp(brand)).as_deref(), Some("video/mp4"));
+        }
+        assert_eq!(sniff(&ftyp(b"qt  ")).as_deref(), Some("video/quicktime"));
+        assert_eq!(sniff(&ftyp(b"M4A ")).as_deref(), Some("audio/mp4"));
+        assert_eq!(sniff(&ftyp(b"avif")).as_deref(), Some("image/avif"));
+        assert_eq!(sniff(&ftyp(b"crx ")), None);
+        assert_eq!(sniff(b"\x00\x00\x00\x18ftyp"), None);
+    }
+
+    #[test]
+    fn should_not_sniff_unknown_content() {
+        assert_eq!(sniff(b""), None);
+        assert_eq!(sniff(b"\xff"), None);
+        assert_eq!(sniff(b"plain text"), None);
+    }
+
+    #[test]
+    fn should_count_gif_frames() {
+        assert_eq!(gif_frames(&gif(0)), 0);
+        assert_eq!(gif_frames(&gif(1)), 1);
+        assert_eq!(gif_frames(&gif(3)), 3);
+
+        let truncated = gif(2);
+        assert_eq!(gif_frames(&truncated[..GIF_HEADER.len() + GIF_CONTROL.len() + 5]), 0);
+        assert_eq!(gif_frames(&truncated[..truncated.len() - 3]), 2);
+        assert_eq!(gif_frames(b"GIF89a"), 0);
+    }
+
+    #[test]
+    fn should_determine_kind_from_mime() {
+        let static_gif = gif(1);
+        let animated_gif = gif(2);
+
+        assert_eq!(AttachmentKind::from_mime(&mime::IMAGE_PNG, b""), AttachmentKind::Image);
+        assert_eq!(AttachmentKind::from_mime(&mime::IMAGE_GIF, &static_gif), AttachmentKind::Image);
+        assert_eq!(AttachmentKind::from_mime(&mime::IMAGE_GIF, &animated_gif), AttachmentKind::Gifv);
+        assert_eq!(AttachmentKind::from_mime(&mime::IMAGE_GIF, b"GIF89a"), AttachmentKind::Image);
+        assert_eq!(AttachmentKind::from_mime(&"video/mp4".parse().unwrap(), b""), AttachmentKind::Video);
+        assert_eq!(AttachmentKind::from_mime(&"audio/mpeg".parse().unwrap(), b""), AttachmentKind::Audio);
+        assert_eq!(AttachmentKind::from_mime(&mime::APPLICATION_OCTET_STREAM, b""), AttachmentKind::Unknown);
+    }
+
+    fn check(platform: Platform, kinds: &[AttachmentKind]) -> Result<(), AttachmentViolation> {
+        platform.capabilities().check(kinds.iter().copied())
+    }
+
+    ///Checks restrictions that all social media have in common.
+    fn check_common(platform: Platform) {
+        assert_eq!(check(platform, &[]), Ok(()));
+        assert_eq!(check(platform, &[AttachmentKind::Image; 4]), Ok(()));
+        assert_eq!(check(platform, &[AttachmentKind::Image; 5]), Err(AttachmentViolation::TooManyImages(4)));
+        assert_eq!(check(platform, &[AttachmentKind::Video]), Ok(()));
+        assert_eq!(check(platform, &[AttachmentKind::Gifv]), Ok(()));
+        assert_eq!(check(platform, &[AttachmentKind::Video, AttachmentKind::Gifv]), Err(AttachmentViolation::TooManyVideos(1)));
+        assert_eq!(check(platform, &[AttachmentKind::Image, AttachmentKind::Video]), Err(AttachmentViolation::MixedMedia));
+        assert_eq!(check(platform, &[AttachmentKind::Image, AttachmentKind::Unknown]), Err(AttachmentViolation::Unsupported(AttachmentKind::Unknown)));
+    }
+
+    #[test]
+    fn should_check_twitter_attachments() {
+        check_common(Platform::Twitter);
+        assert_eq!(check(Platform::Twitter, &[AttachmentKind::Audio]), Err(AttachmentViolation::Unsupported(AttachmentKind::Audio)));
+    }
+
+    #[test]
+    fn should_check_gab_attachments() {
+        check_common(Platform::Gab);
+        assert_eq!(check(Platform::Gab, &[AttachmentKind::Audio]), Err(AttachmentViolation::Unsupported(AttachmentKind::Audio)));
+    }
+
+    #[test]
+    fn should_check_mastodon_attachments() {
+        check_common(Platform::Mastodon);
+        assert_eq!(check(Platform::Mastodon, &[AttachmentKind::Audio]), Ok(()));
+        assert_eq!(check(Platform::Mastodon, &[AttachmentKind::Audio, AttachmentKind::Video]), Err(AttachmentViolation::TooManyVideos(1)));
+        assert_eq!(check(Platform::Mastodon, &[AttachmentKind::Image, AttachmentKind::Audio]), Err(AttachmentViolation::MixedMedia));
+    }
+
+    #[test]
+    fn should_check_minds_attachments() {
+        check_common(Platform::Minds);
+        assert_eq!(check(Platform::Minds, &[AttachmentKind::Audio]), Err(AttachmentViolation::Unsupported(AttachmentKind::Audio)));
+    }
 
-        let name = path.as_ref().file_name().unwrap().to_string_lossy().to_string();
-        let mime = mime_guess::from_path(path).first_or_octet_stream();
+    #[test]
+    fn should_trim_unsupported_attachments() {
+        let attachments = [
+            Image::from_bytes("unknown.txt".to_owned(), b"plain text".to_vec()),
+            Image::from_bytes("audio.mp3".to_owned(), b"ID3\x04\x00".to_vec()),
+            Image::from_bytes("image.gif".to_owned(), gif(1)),
+        ];
+        let names = |platform: Platform| platform.capabilities().trim(&attachments).iter().map(|image| image.name.as_str()).collect::<Vec<_>>();
 
-        Ok(Image { name, mime, _file, mmap })
+        assert_eq!(names(Platform::Twitter), ["image.gif"]);
+        assert_eq!(names(Platform::Mastodon), ["audio.mp3"]);
     }
 }
@@ -0,0 +1,787 @@
+//!Image metadata utilities
+//!
+//!Removes EXIF, XMP, IPTC and textual metadata from JPEG, PNG and WebP images without re-encoding them.
+
+use mime_guess::{mime, Mime};
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+
+///Describes which metadata survives stripping.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MetadataFilter {
+    ///Keeps EXIF orientation, so that image is displayed correctly.
+    ///
+    ///Only orientation tag is preserved, the rest of EXIF is removed.
+    pub keep_orientation: bool,
+    ///Keeps embedded ICC color profile.
+    pub keep_icc_profile: bool,
+}
+
+impl Default for MetadataFilter {
+    fn default() -> Self {
+        Self {
+            keep_orientation: true,
+            keep_icc_profile: true,
+        }
+    }
+}
+
+///Image's content cannot be parsed, hence its metadata cannot be stripped.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MalformedImage;
+
+impl fmt::Display for MalformedImage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Image is malformed, so its metadata cannot be stripped")
+    }
+}
+
+impl Error for MalformedImage {}
+
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+const ORIENTATION_TAG: u16 = 0x0112;
+
+fn read_u16(data: &[u8], pos: usize, big_endian: bool) -> Option<u16> {
+    let bytes = [*data.get(pos)?, *data.get(pos + 1)?];
+    Some(match big_endian {
+        true => u16::from_be_bytes(bytes),
+        false => u16::from_le_bytes(bytes),
+    })
+}
+
+fn read_u32(data: &[u8], pos: usize, big_endian: bool) -> Option<u32> {
+    let bytes = [*data.get(pos)?, *data.get(pos + 1)?, *data.get(pos + 2)?, *data.get(pos + 3)?];
+    Some(match big_endian {
+        true => u32::from_be_bytes(bytes),
+        false => u32::from_le_bytes(bytes),
+    })
+}
+
+///Reads orientation tag from TIFF structure of EXIF.
+///
+///Accepts content with or without `Exif\0\0` header.
+fn tiff_orientation(tiff: &[u8]) -> Option<u16> {
+    let tiff = match tiff.starts_with(EXIF_HEADER) {
+        true => &tiff[EXIF_HEADER.len()..],
+        false => tiff,
+    };
+
+    let big_endian = match tiff.get(..4)? {
+        b"MM\x00\x2A" => true,
+        b"II\x2A\x00" => false,
+        _ => return None,
+    };
+
+    let ifd = read_u32(tiff, 4, big_endian)? as usize;
+    let entries = read_u16(tiff, ifd, big_endian)? as usize;
+
+    for idx in 0..entries {
+        let entry = ifd + 2 + idx * 12;
+        if read_u16(tiff, entry, big_endian)? == ORIENTATION_TAG {
+            return match read_u16(tiff, entry + 8, big_endian)? {
+                orientation @ 1..=8 => Some(orientation),
+                _ => None,
+            };
+        }
+    }
+
+    None
+}
+
+///Creates TIFF structure with only orientation tag in it.
+fn orientation_tiff(orientation: u16) -> Vec<u8> {
+    let mut tiff = Vec::with_capacity(26);
+    tiff.extend_from_slice(b"MM\x00\x2A");
+    //Offset of the first IFD
+    tiff.extend_from_slice(&8u32.to_be_bytes());
+    //Number of entries
+    tiff.extend_from_slice(&1u16.to_be_bytes());
+    //Tag, type SHORT, count and value padded to 4 bytes
+    tiff.extend_from_slice(&ORIENTATION_TAG.to_be_bytes());
+    tiff.extend_from_slice(&3u16.to_be_bytes());
+    tiff.extend_from_slice(&1u32.to_be_bytes());
+    tiff.extend_from_slice(&orientation.to_be_bytes());
+    tiff.extend_from_slice(&[0, 0]);
+    //No next IFD
+    tiff.extend_from_slice(&0u32.to_be_bytes());
+    tiff
+}
+
+///Retrieves EXIF orientation of image, if any.
+///
+///Returns value in range `1..=8`, as defined by EXIF.
+pub fn orientation(data: &[u8], mime: &Mime) -> Option<u16> {
+    match mime.subtype().as_str() {
+        "jpeg" => jpeg_segments(data)?.iter().filter_map(|segment| match segment {
+            JpegSegment::Marker(0xE1, payload) if payload.starts_with(EXIF_HEADER) => tiff_orientation(payload),
+            _ => None,
+        }).next(),
+        "png" => png_chunks(data)?.iter().filter_map(|chunk| match chunk.kind {
+            b"eXIf" => tiff_orientation(chunk.data),
+            _ => None,
+        }).next(),
+        "webp" => webp_chunks(data)?.iter().filter_map(|chunk| match chunk.kind {
+            b"EXIF" => tiff_orientation(chunk.data),
+            _ => None,
+        }).next(),
+        _ => None,
+    }
+}
+
+///Removes metadata from image, keeping only what is allowed by `filter`.
+///
+///Supports JPEG, PNG and WebP. Image data itself is not re-encoded.
+///
+///Returns `None` if format is not supported or there is nothing to strip.
+///Fails if content of supported format cannot be parsed, as it may still carry metadata.
+pub fn strip(data: &[u8], mime: &Mime, filter: &MetadataFilter) -> Result<Option<Vec<u8>>, MalformedImage> {
+    if mime.type_() != mime::IMAGE {
+        return Ok(None);
+    }
+
+    match mime.subtype().as_str() {
+        "jpeg" => strip_jpeg(data, filter),
+        "png" => strip_png(data, filter),
+        "webp" => strip_webp(data, filter),
+        _ => Ok(None),
+    }
+}
+
+const ICC_HEADER: &[u8] = b"ICC_PROFILE\0";
+///Maximum size of ICC profile's part within single APP2 segment.
+#[cfg(feature = "image-processing")]
+const ICC_SEGMENT_SIZE: usize = u16::MAX as usize - 2 - ICC_HEADER.len() - 2;
+///Maximum size of decompressed ICC profile in PNG.
+#[cfg(feature = "image-processing")]
+const MAX_ICC_PROFILE_SIZE: usize = 16 * 1024 * 1024;
+
+#[cfg(feature = "image-processing")]
+///Retrieves embedded ICC color profile of JPEG, PNG or WebP image, if any.
+pub fn icc_profile(data: &[u8], mime: &Mime) -> Option<Vec<u8>> {
+    match mime.subtype().as_str() {
+        "jpeg" => {
+            //Profile can be split into multiple segments, each with its sequence number.
+            let mut parts = jpeg_segments(data)?.into_iter().filter_map(|segment| match segment {
+                JpegSegment::Marker(0xE2, payload) if payload.starts_with(ICC_HEADER) => Some((*payload.get(ICC_HEADER.len())?, payload.get(ICC_HEADER.len() + 2..)?)),
+                _ => None,
+            }).collect::<Vec<_>>();
+
+            if parts.is_empty() {
+                return None;
+            }
+
+            parts.sort_by_key(|(seq, _)| *seq);
+            Some(parts.into_iter().flat_map(|(_, part)| part.iter().copied()).collect())
+        },
+        "png" => png_chunks(data)?.iter().find(|chunk| chunk.kind == b"iCCP").and_then(|chunk| {
+            //Null terminated profile name and compression method precede compressed profile.
+            let name_end = chunk.data.iter().position(|byte| *byte == 0)?;
+            miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(chunk.data.get(name_end + 2..)?, MAX_ICC_PROFILE_SIZE).ok()
+        }),
+        "webp" => webp_chunks(data)?.iter().find(|chunk| chunk.kind == b"ICCP").map(|chunk| chunk.data.to_vec()),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "image-processing")]
+///Embeds ICC color profile into JPEG or PNG image without any profile.
+///
+///Returns `None` if format is not supported or content is malformed.
+pub fn embed_icc_profile(data: &[u8], mime: &Mime, profile: &[u8]) -> Option<Vec<u8>> {
+    match mime.subtype().as_str() {
+        "jpeg" => {
+            let segments = jpeg_segments(data)?;
+            let count = profile.chunks(ICC_SEGMENT_SIZE).len();
+            if count == 0 || count > u8::MAX as usize {
+                return None;
+            }
+
+            //JFIF marker must stay right after start of image
+            let mut pos = 2;
+            if let Some(JpegSegment::Marker(0xE0, payload)) = segments.first() {
+                pos += 4 + payload.len();
+            }
+
+            let mut result = Vec::with_capacity(data.len() + profile.len() + count * (4 + ICC_HEADER.len() + 2));
+            result.extend_from_slice(&data[..pos]);
+            for (idx, part) in profile.chunks(ICC_SEGMENT_SIZE).enumerate() {
+                result.extend_from_slice(&[0xFF, 0xE2]);
+                result.extend_from_slice(&((2 + ICC_HEADER.len() + 2 + part.len()) as u16).to_be_bytes());
+                result.extend_from_slice(ICC_HEADER);
+                result.extend_from_slice(&[idx as u8 + 1, count as u8]);
+                result.extend_from_slice(part);
+            }
+            result.extend_from_slice(&data[pos..]);
+            Some(result)
+        },
+        "png" => {
+            let chunks = png_chunks(data)?;
+            if chunks.first()?.kind != b"IHDR" {
+                return None;
+            }
+
+            let mut content = b"iCCP".to_vec();
+            content.extend_from_slice(b"ICC Profile\0\0");
+            content.extend_from_slice(&miniz_oxide::deflate::compress_to_vec_zlib(profile, 6));
+
+            let mut result = Vec::with_capacity(data.len() + content.len() + 8);
+            result.extend_from_slice(PNG_SIGNATURE);
+            //Profile must precede palette and image data
+            result.extend_from_slice(chunks[0].raw);
+            result.extend_from_slice(&(content.len() as u32 - 4).to_be_bytes());
+            result.extend_from_slice(&content);
+            result.extend_from_slice(&crc32(&content).to_be_bytes());
+            for chunk in chunks[1..].iter() {
+                result.extend_from_slice(chunk.raw);
+            }
+            Some(result)
+        },
+        _ => None,
+    }
+}
+
+enum JpegSegment<'a> {
+    ///Marker with payload, excluding length.
+    Marker(u8, &'a [u8]),
+    ///Marker without payload.
+    Standalone(u8),
+    ///Start of scan with the rest of file.
+    Scan(&'a [u8]),
+}
+
+fn jpeg_segments(data: &[u8]) -> Option<Vec<JpegSegment<'_>>> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+
+    let mut segments = Vec::new();
+    let mut pos = 2;
+
+    loop {
+        if *data.get(pos)? != 0xFF {
+            return None;
+        }
+
+        let marker = *data.get(pos + 1)?;
+        match marker {
+            //Fill byte
+            0xFF => pos += 1,
+            0x01 | 0xD0..=0xD7 => {
+                segments.push(JpegSegment::Standalone(marker));
+                pos += 2;
+            },
+            0xDA => {
+                segments.push(JpegSegment::Scan(&data[pos..]));
+                return Some(segments);
+            },
+            _ => {
+                let len = read_u16(data, pos + 2, true)? as usize;
+                if len < 2 {
+                    return None;
+                }
+
+                let payload = data.get(pos + 4..pos + 2 + len)?;
+                segments.push(JpegSegment::Marker(marker, payload));
+                pos += 2 + len;
+            },
+        }
+    }
+}
+
+fn strip_jpeg(data: &[u8], filter: &MetadataFilter) -> Result<Option<Vec<u8>>, MalformedImage> {
+    let segments = jpeg_segments(data).ok_or(MalformedImage)?;
+
+    let mut result = Vec::with_capacity(data.len());
+    let mut stripped = false;
+    result.extend_from_slice(&[0xFF, 0xD8]);
+
+    for segment in segments.iter() {
+        let (marker, payload) = match segment {
+            JpegSegment::Standalone(marker) => {
+                result.extend_from_slice(&[0xFF, *marker]);
+                continue;
+            },
+            JpegSegment::Scan(scan) => {
+                result.extend_from_slice(scan);
+                continue;
+            },
+            JpegSegment::Marker(marker, payload) => match marker {
+                //EXIF, keeping orientation in its place if needed
+                0xE1 if payload.starts_with(EXIF_HEADER) => {
+                    stripped = true;
+                    match filter.keep_orientation {
+                        true => match tiff_orientation(payload) {
+                            Some(orientation) => {
+                                let mut exif = EXIF_HEADER.to_vec();
+                                exif.extend_from_slice(&orientation_tiff(orientation));
+                                (*marker, Cow::Owned(exif))
+                            },
+                            None => continue,
+                        },
+                        false => continue,
+                    }
+                },
+                0xE2 if filter.keep_icc_profile && payload.starts_with(ICC_HEADER) => (*marker, Cow::Borrowed(*payload)),
+                //JFIF and Adobe markers affect decoding
+                0xE0 | 0xEE => (*marker, Cow::Borrowed(*payload)),
+                //The rest of application markers (XMP, IPTC and etc) and comments
+                0xE1..=0xEF | 0xFE => {
+                    stripped = true;
+                    continue;
+                },
+                _ => (*marker, Cow::Borrowed(*payload)),
+            },
+        };
+
+        result.extend_from_slice(&[0xFF, marker]);
+        result.extend_from_slice(&(payload.len() as u16 + 2).to_be_bytes());
+        result.extend_from_slice(&payload);
+    }
+
+    match stripped {
+        true => Ok(Some(result)),
+        false => Ok(None),
+    }
+}
+
+const PNG_SIGNATURE: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+struct Chunk<'a> {
+    kind: &'a [u8],
+    data: &'a [u8],
+    ///Whole chunk, including header and trailer.
+    raw: &'a [u8],
+}
+
+fn png_chunks(data: &[u8]) -> Option<Vec<Chunk<'_>>> {
+    if !data.starts_with(PNG_SIGNATURE) {
+        return None;
+    }
+
+    let mut chunks = Vec::new();
+    let mut pos = PNG_SIGNATURE.len();
+
+    while pos < data.len() {
+        let len = read_u32(data, pos, true)? as usize;
+        let end = pos.checked_add(12)?.checked_add(len)?;
+
+        chunks.push(Chunk {
+            kind: data.get(pos + 4..pos + 8)?,
+            data: data.get(pos + 8..pos + 8 + len)?,
+            raw: data.get(pos..end)?,
+        });
+        pos = end;
+    }
+
+    Some(chunks)
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = match crc & 1 {
+                1 => (crc >> 1) ^ 0xEDB8_8320,
+                _ => crc >> 1,
+            };
+        }
+    }
+    !crc
+}
+
+fn strip_png(data: &[u8], filter: &MetadataFilter) -> Result<Option<Vec<u8>>, MalformedImage> {
+    let chunks = png_chunks(data).ok_or(MalformedImage)?;
+
+    let mut result = Vec::with_capacity(data.len());
+    let mut stripped = false;
+    result.extend_from_slice(PNG_SIGNATURE);
+
+    for chunk in chunks.iter() {
+        match chunk.kind {
+            b"eXIf" => {
+                stripped = true;
+                if let (true, Some(orientation)) = (filter.keep_orientation, tiff_orientation(chunk.data)) {
+                    let mut content = b"eXIf".to_vec();
+                    content.extend_from_slice(&orientation_tiff(orientation));
+
+                    result.extend_from_slice(&(content.len() as u32 - 4).to_be_bytes());
+                    result.extend_from_slice(&content);
+                    result.extend_from_slice(&crc32(&content).to_be_bytes());
+                }
+            },
+            b"iCCP" if filter.keep_icc_profile => result.extend_from_slice(chunk.raw),
+            b"iCCP" | b"tEXt" | b"zTXt" | b"iTXt" | b"tIME" => stripped = true,
+            _ => result.extend_from_slice(chunk.raw),
+        }
+    }
+
+    match stripped {
+        true => Ok(Some(result)),
+        false => Ok(None),
+    }
+}
+
+//Flags of VP8X chunk
+const WEBP_ICC_FLAG: u8 = 0x20;
+const WEBP_EXIF_FLAG: u8 = 0x08;
+const WEBP_XMP_FLAG: u8 = 0x04;
+///Size of VP8X chunk's payload: flags, reserved bytes and canvas size.
+const WEBP_VP8X_LEN: usize = 10;
+
+fn webp_chunks(data: &[u8]) -> Option<Vec<Chunk<'_>>> {
+    if data.get(..4)? != b"RIFF" || data.get(8..12)? != b"WEBP" {
+        return None;
+    }
+
+    let mut chunks = Vec::new();
+    let mut pos = 12;
+
+    while pos < data.len() {
+        let len = read_u32(data, pos + 4, false)? as usize;
+        //Chunks are padded to even size
+        let end = pos.checked_add(8)?.checked_add(len + (len & 1))?.min(data.len());
+
+        chunks.push(Chunk {
+            kind: data.get(pos..pos + 4)?,
+            data: data.get(pos + 8..pos + 8 + len)?,
+            raw: data.get(pos..end)?,
+        });
+        pos = end;
+    }
+
+    Some(chunks)
+}
+
+fn strip_webp(data: &[u8], filter: &MetadataFilter) -> Result<Option<Vec<u8>>, MalformedImage> {
+    let chunks = webp_chunks(data).ok_or(MalformedImage)?;
+
+    let mut result = Vec::with_capacity(data.len());
+    let mut stripped = false;
+    let mut flags = 0u8;
+    let mut vp8x = None;
+
+    result.extend_from_slice(b"RIFF\0\0\0\0WEBP");
+
+    for chunk in chunks.iter() {
+        match chunk.kind {
+            b"VP8X" => {
+                if chunk.data.len() < WEBP_VP8X_LEN {
+                    return Err(MalformedImage);
+                }
+                vp8x = Some(result.len());
+                result.extend_from_slice(chunk.raw);
+            },
+            b"EXIF" => {
+                stripped = true;
+                if let (true, Some(orientation)) = (filter.keep_orientation, tiff_orientation(chunk.data)) {
+                    let tiff = orientation_tiff(orientation);
+                    result.extend_from_slice(b"EXIF");
+                    result.extend_from_slice(&(tiff.len() as u32).to_le_bytes());
+                    result.extend_from_slice(&tiff);
+                    flags |= WEBP_EXIF_FLAG;
+                }
+            },
+            b"ICCP" if filter.keep_icc_profile => {
+                result.extend_from_slice(chunk.raw);
+                flags |= WEBP_ICC_FLAG;
+            },
+            b"ICCP" | b"XMP " => stripped = true,
+            _ => result.extend_from_slice(chunk.raw),
+        }
+    }
+
+    if !stripped {
+        return Ok(None);
+    }
+
+    //Metadata can be present only in extended format, so VP8X must exist.
+    let vp8x_flags = vp8x.and_then(|vp8x| result.get_mut(vp8x + 8)).ok_or(MalformedImage)?;
+    *vp8x_flags = (*vp8x_flags & !(WEBP_ICC_FLAG | WEBP_EXIF_FLAG | WEBP_XMP_FLAG)) | flags;
+
+    let riff_size = (result.len() - 8) as u32;
+    result[4..8].copy_from_slice(&riff_size.to_le_bytes());
+
+    Ok(Some(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ICC: &[u8] = b"fake icc profile";
+    const SCAN: &[u8] = b"\xff\xda\x00\x08\x01\x01\x00\x00\x3f\x00\x12\x34\xff\xd9";
+
+    ///EXIF with camera's make and orientation.
+    fn exif(orientation: u16) -> Vec<u8> {
+        let mut tiff = b"II\x2a\x00\x08\x00\x00\x00\x02\x00".to_vec();
+        //Make, ASCII, with value in the entry itself
+        tiff.extend_from_slice(b"\x0f\x01\x02\x00\x04\x00\x00\x00fie\x00");
+        tiff.extend_from_slice(&ORIENTATION_TAG.to_le_bytes());
+        tiff.extend_from_slice(b"\x03\x00\x01\x00\x00\x00");
+        tiff.extend_from_slice(&orientation.to_le_bytes());
+        tiff.extend_from_slice(b"\x00\x00\x00\x00\x00\x00");
+        tiff
+    }
+
+    fn jpeg_segment(marker: u8, payload: &[u8]) -> Vec<u8> {
+        let mut segment = vec![0xFF, marker];
+        segment.extend_from_slice(&(payload.len() as u16 + 2).to_be_bytes());
+        segment.extend_from_slice(payload);
+        segment
+    }
+
+    fn jpeg(segments: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = vec![0xFF, 0xD8];
+        for segment in segments {
+            data.extend_from_slice(segment);
+        }
+        data.extend_from_slice(SCAN);
+        data
+    }
+
+    fn jpeg_with_metadata() -> Vec<u8> {
+        let mut icc = ICC_HEADER.to_vec();
+        icc.extend_from_slice(b"\x01\x01");
+        icc.extend_from_slice(ICC);
+
+        jpeg(&[
+            jpeg_segment(0xE0, b"JFIF\x00\x01\x01\x00\x00\x01\x00\x01\x00\x00"),
+            jpeg_segment(0xE1, &[EXIF_HEADER, &exif(6)].concat()),
+            jpeg_segment(0xE2, &icc),
+            jpeg_segment(0xED, b"Photoshop 3.0\x00IPTC"),
+            jpeg_segment(0xFE, b"comment"),
+            jpeg_segment(0xDB, &[0; 65]),
+        ])
+    }
+
+    fn jpeg_markers(data: &[u8]) -> Vec<u8> {
+        jpeg_segments(data).expect("Valid JPEG").iter().filter_map(|segment| match segment {
+            JpegSegment::Marker(marker, _) => Some(*marker),
+            _ => None,
+        }).collect()
+    }
+
+    fn png_chunk(kind: &[u8], data: &[u8]) -> Vec<u8> {
+        let content = [kind, data].concat();
+        let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+        chunk.extend_from_slice(&content);
+        chunk.extend_from_slice(&crc32(&content).to_be_bytes());
+        chunk
+    }
+
+    fn png(chunks: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = PNG_SIGNATURE.to_vec();
+        data.extend_from_slice(&png_chunk(b"IHDR", b"\x00\x00\x00\x01\x00\x00\x00\x01\x08\x02\x00\x00\x00"));
+        for chunk in chunks {
+            data.extend_from_slice(chunk);
+        }
+        data.extend_from_slice(&png_chunk(b"IDAT", b"\x78\x9c\x63\x60\x60\x60\x00\x00\x00\x04\x00\x01"));
+        data.extend_from_slice(&png_chunk(b"IEND", b""));
+        data
+    }
+
+    fn png_with_metadata() -> Vec<u8> {
+        png(&[
+            png_chunk(b"iCCP", b"icc\x00\x00profile"),
+            png_chunk(b"eXIf", &exif(3)),
+            png_chunk(b"tEXt", b"Author\x00fie"),
+            png_chunk(b"tIME", b"\x07\xea\x0a\x13\x00\x00\x00"),
+        ])
+    }
+
+    fn png_kinds(data: &[u8]) -> Vec<&[u8]> {
+        png_chunks(data).expect("Valid PNG").iter().map(|chunk| chunk.kind).collect()
+    }
+
+    fn webp_chunk(kind: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut chunk = kind.to_vec();
+        chunk.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(data);
+        if data.len() % 2 == 1 {
+            chunk.push(0);
+        }
+        chunk
+    }
+
+    fn webp(chunks: &[Vec<u8>]) -> Vec<u8> {
+        let content = chunks.concat();
+        let mut data = b"RIFF".to_vec();
+        data.extend_from_slice(&(content.len() as u32 + 4).to_le_bytes());
+        data.extend_from_slice(b"WEBP");
+        data.extend_from_slice(&content);
+        data
+    }
+
+    fn webp_with_metadata() -> Vec<u8> {
+        let flags = WEBP_ICC_FLAG | WEBP_EXIF_FLAG | WEBP_XMP_FLAG;
+        webp(&[
+            webp_chunk(b"VP8X", &[flags, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+            webp_chunk(b"ICCP", ICC),
+            //Odd size to check padding
+            webp_chunk(b"VP8 ", b"frame"),
+            webp_chunk(b"EXIF", &exif(8)),
+            webp_chunk(b"XMP ", b"<x:xmpmeta/>"),
+        ])
+    }
+
+    fn webp_mime() -> Mime {
+        "image/webp".parse().expect("To parse valid MIME")
+    }
+
+    fn webp_kinds(data: &[u8]) -> Vec<&[u8]> {
+        webp_chunks(data).expect("Valid WebP").iter().map(|chunk| chunk.kind).collect()
+    }
+
+    const ALL: MetadataFilter = MetadataFilter {
+        keep_orientation: true,
+        keep_icc_profile: true,
+    };
+    const NOTHING: MetadataFilter = MetadataFilter {
+        keep_orientation: false,
+        keep_icc_profile: false,
+    };
+
+    #[test]
+    fn should_strip_jpeg_keeping_orientation() {
+        let data = jpeg_with_metadata();
+        assert_eq!(orientation(&data, &mime::IMAGE_JPEG), Some(6));
+
+        let stripped = strip(&data, &mime::IMAGE_JPEG, &ALL).expect("Valid JPEG").expect("To strip JPEG");
+        assert_eq!(jpeg_markers(&stripped), [0xE0, 0xE1, 0xE2, 0xDB]);
+        assert_eq!(orientation(&stripped, &mime::IMAGE_JPEG), Some(6));
+        assert!(!stripped.windows(3).any(|window| window == b"fie"));
+        assert!(stripped.ends_with(SCAN));
+
+        let stripped = strip(&data, &mime::IMAGE_JPEG, &NOTHING).expect("Valid JPEG").expect("To strip JPEG");
+        assert_eq!(jpeg_markers(&stripped), [0xE0, 0xDB]);
+        assert_eq!(orientation(&stripped, &mime::IMAGE_JPEG), None);
+    }
+
+    #[test]
+    fn should_keep_jpeg_icc_profile_only_if_requested() {
+        let data = jpeg_with_metadata();
+        let filter = MetadataFilter {
+            keep_orientation: false,
+            keep_icc_profile: true,
+        };
+
+        let stripped = strip(&data, &mime::IMAGE_JPEG, &filter).expect("Valid JPEG").expect("To strip JPEG");
+        assert_eq!(jpeg_markers(&stripped), [0xE0, 0xE2, 0xDB]);
+        assert!(stripped.windows(ICC.len()).any(|window| window == ICC));
+    }
+
+    #[test]
+    fn should_strip_png_with_valid_crc() {
+        let data = png_with_metadata();
+        assert_eq!(orientation(&data, &mime::IMAGE_PNG), Some(3));
+
+        let stripped = strip(&data, &mime::IMAGE_PNG, &ALL).expect("Valid PNG").expect("To strip PNG");
+        assert_eq!(png_kinds(&stripped), [&b"IHDR"[..], b"iCCP", b"eXIf", b"IDAT", b"IEND"]);
+        assert_eq!(orientation(&stripped, &mime::IMAGE_PNG), Some(3));
+        for chunk in png_chunks(&stripped).unwrap() {
+            let crc = &chunk.raw[chunk.raw.len() - 4..];
+            assert_eq!(crc, crc32(&chunk.raw[4..chunk.raw.len() - 4]).to_be_bytes(), "CRC of {:?}", chunk.kind);
+        }
+        //Well known CRC of empty IEND
+        assert!(stripped.ends_with(b"IEND\xae\x42\x60\x82"));
+
+        let stripped = strip(&data, &mime::IMAGE_PNG, &NOTHING).expect("Valid PNG").expect("To strip PNG");
+        assert_eq!(png_kinds(&stripped), [&b"IHDR"[..], b"IDAT", b"IEND"]);
+    }
+
+    #[test]
+    fn should_strip_webp_and_rewrite_riff() {
+        let data = webp_with_metadata();
+        assert_eq!(orientation(&data, &webp_mime()), Some(8));
+
+        let stripped = strip(&data, &webp_mime(), &ALL).expect("Valid WebP").expect("To strip WebP");
+        assert_eq!(webp_kinds(&stripped), [&b"VP8X"[..], b"ICCP", b"VP8 ", b"EXIF"]);
+        assert_eq!(read_u32(&stripped, 4, false), Some(stripped.len() as u32 - 8));
+        assert_eq!(stripped[20], WEBP_ICC_FLAG | WEBP_EXIF_FLAG);
+        assert_eq!(orientation(&stripped, &webp_mime()), Some(8));
+
+        let stripped = strip(&data, &webp_mime(), &NOTHING).expect("Valid WebP").expect("To strip WebP");
+        assert_eq!(webp_kinds(&stripped), [&b"VP8X"[..], b"VP8 "]);
+        assert_eq!(read_u32(&stripped, 4, false), Some(stripped.len() as u32 - 8));
+        assert_eq!(stripped[20], 0);
+    }
+
+    #[test]
+    fn should_not_strip_clean_images() {
+        let clean_jpeg = jpeg(&[jpeg_segment(0xE0, b"JFIF\x00\x01\x01\x00\x00\x01\x00\x01\x00\x00")]);
+        let clean_png = png(&[]);
+        let clean_webp = webp(&[webp_chunk(b"VP8 ", b"frame")]);
+
+        assert_eq!(strip(&clean_jpeg, &mime::IMAGE_JPEG, &NOTHING), Ok(None));
+        assert_eq!(strip(&clean_png, &mime::IMAGE_PNG, &NOTHING), Ok(None));
+        assert_eq!(strip(&clean_webp, &webp_mime(), &NOTHING), Ok(None));
+        assert_eq!(strip(&jpeg_with_metadata(), &mime::IMAGE_GIF, &NOTHING), Ok(None));
+        assert_eq!(strip(&jpeg_with_metadata(), &mime::TEXT_PLAIN, &NOTHING), Ok(None));
+    }
+
+    #[test]
+    fn should_not_panic_on_truncated_images() {
+        let images = [
+            (mime::IMAGE_JPEG, jpeg_with_metadata()),
+            (mime::IMAGE_PNG, png_with_metadata()),
+            (webp_mime(), webp_with_metadata()),
+        ];
+
+        for (mime, data) in images.iter() {
+            for len in 0..data.len() {
+                for filter in [ALL, NOTHING].iter() {
+                    let _ = strip(&data[..len], mime, filter);
+                }
+                orientation(&data[..len], mime);
+            }
+        }
+    }
+
+    #[test]
+    fn should_reject_corrupted_images() {
+        //VP8X without payload at the end
+        let image = webp(&[webp_chunk(b"EXIF", &exif(1)), webp_chunk(b"VP8X", b"")]);
+        assert_eq!(strip(&image, &webp_mime(), &ALL), Err(MalformedImage));
+        //Metadata without VP8X
+        let image = webp(&[webp_chunk(b"VP8 ", b"frame"), webp_chunk(b"XMP ", b"<x:xmpmeta/>")]);
+        assert_eq!(strip(&image, &webp_mime(), &ALL), Err(MalformedImage));
+
+        //Segment's length is less than size of length itself
+        let image = [&b"\xff\xd8\xff\xe1\x00\x01"[..], SCAN].concat();
+        assert_eq!(strip(&image, &mime::IMAGE_JPEG, &ALL), Err(MalformedImage));
+
+        //Chunk's length exceeds content
+        let mut image = png_with_metadata();
+        image[PNG_SIGNATURE.len()..PNG_SIGNATURE.len() + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert_eq!(strip(&image, &mime::IMAGE_PNG, &ALL), Err(MalformedImage));
+
+        //Orientation out of range
+        let image = png(&[png_chunk(b"eXIf", &exif(9))]);
+        assert_eq!(orientation(&image, &mime::IMAGE_PNG), None);
+    }
+
+    #[cfg(feature = "image-processing")]
+    #[test]
+    fn should_carry_icc_profile_between_formats() {
+        let profile = (0..70_000u32).map(|idx| idx as u8).collect::<Vec<_>>();
+        let jpeg = b"\xff\xd8\xff\xe0\x00\x04JF\xff\xda\x00\x02\xff\xd9";
+        let png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x00IHDR\x00\x00\x00\x00\x00\x00\x00\x00IEND\xaeB`\x82";
+
+        let jpeg = embed_icc_profile(jpeg, &mime::IMAGE_JPEG, &profile).expect("To embed into JPEG");
+        //Profile doesn't fit single segment, and JFIF stays first
+        assert_eq!(&jpeg[2..4], b"\xff\xe0");
+        assert_eq!(jpeg_segments(&jpeg).unwrap().iter().filter(|segment| matches!(segment, JpegSegment::Marker(0xE2, _))).count(), 2);
+        assert_eq!(icc_profile(&jpeg, &mime::IMAGE_JPEG).as_deref(), Some(&profile[..]));
+
+        let png = embed_icc_profile(png, &mime::IMAGE_PNG, &profile).expect("To embed into PNG");
+        let chunks = png_chunks(&png).unwrap();
+        assert_eq!(chunks.iter().map(|chunk| chunk.kind).collect::<Vec<_>>(), [&b"IHDR"[..], b"iCCP", b"IEND"]);
+        assert_eq!(icc_profile(&png, &mime::IMAGE_PNG).as_deref(), Some(&profile[..]));
+
+        assert_eq!(icc_profile(b"\xff\xd8\xff\xda", &mime::IMAGE_JPEG), None);
+        assert_eq!(embed_icc_profile(b"GIF89a", &mime::IMAGE_GIF, &profile), None);
+    }
+}
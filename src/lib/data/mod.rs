@@ -1,12 +1,15 @@
 //!Data module
 
 pub mod image;
+pub mod metadata;
 
-pub use image::Image;
+pub use image::{Attachment, AttachmentKind, AttachmentSource, AttachmentViolation, Capabilities, Focus, Image, ImageLimits};
 
 use serde_derive::{Deserialize};
 
 use std::fmt;
+use std::time::Duration;
+use std::str::FromStr;
 
 ///Flags for text posts
 #[derive(Deserialize, Default, Debug, Clone)]
@@ -24,7 +27,7 @@ pub struct Post {
     ///Hashtags to add
     pub tags: Vec<String>,
     ///Attachments
-    pub images: Vec<String>,
+    pub images: Vec<Attachment>,
     #[serde(default)]
     ///Flags
     pub flags: PostFlags,
@@ -71,6 +74,220 @@ impl Into<PostId> for &'static str {
         PostId::Str(self)
     }
 }
+
+///References to the same post on each social media.
+///
+///Used to perform operations on already published posts.
+///Missing reference means that platform is skipped.
+#[derive(Default, Clone, Debug)]
+pub struct PostRefs {
+    ///Twitter's post ID
+    pub twitter: Option<PostId>,
+    ///Gab's post ID
+    pub gab: Option<PostId>,
+    ///Mastodon's post ID
+    pub mastodon: Option<PostId>,
+    ///Minds's post ID
+    pub minds: Option<PostId>,
+}
+
+///Social media's account
+#[derive(Clone, Debug)]
+pub struct Account {
+    ///Account's ID
+    pub id: String,
+    ///Unique name used to mention account.
+    pub username: String,
+    ///Name to display.
+    pub name: String,
+}
+
+impl fmt::Display for Account {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (@{}, Id={})", self.name, self.username, self.id)
+    }
+}
+
+///Social media
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Platform {
+    ///Twitter
+    Twitter,
+    ///Gab
+    Gab,
+    ///Mastodon
+    Mastodon,
+    ///Minds
+    Minds,
+}
+
+impl Platform {
+    ///Returns restrictions on attachments within single post.
+    pub fn capabilities(&self) -> &'static Capabilities {
+        match self {
+            Platform::Twitter => &Capabilities::TWITTER,
+            Platform::Gab => &Capabilities::GAB,
+            Platform::Mastodon => &Capabilities::MASTODON,
+            Platform::Minds => &Capabilities::MINDS,
+        }
+    }
+
+    ///Returns how long uploaded media can be attached to new posts.
+    ///
+    ///`None` means that media can be attached only to single post.
+    pub fn media_lifetime(&self) -> Option<Duration> {
+        match self {
+            //Media ID can be used in multiple tweets, until it expires.
+            &Platform::Twitter => Some(Duration::from_secs(24 * 60 * 60)),
+            //Media belongs to the first status/activity it is attached to.
+            &Platform::Gab | &Platform::Mastodon | &Platform::Minds => None,
+        }
+    }
+
+    ///Returns limits on uploaded images.
+    pub fn image_limits(&self) -> &'static ImageLimits {
+        match self {
+            Platform::Twitter => &ImageLimits::TWITTER,
+            Platform::Gab => &ImageLimits::GAB,
+            Platform::Mastodon => &ImageLimits::MASTODON,
+            Platform::Minds => &ImageLimits::MINDS,
+        }
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Platform::Twitter => f.write_str("Twitter"),
+            Platform::Gab => f.write_str("Gab"),
+            Platform::Mastodon => f.write_str("Mastodon"),
+            Platform::Minds => f.write_str("Minds"),
+        }
+    }
+}
+
+impl FromStr for Platform {
+    type Err = &'static str;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.to_ascii_lowercase().as_str() {
+            "twitter" => Ok(Platform::Twitter),
+            "gab" => Ok(Platform::Gab),
+            "mastodon" => Ok(Platform::Mastodon),
+            "minds" => Ok(Platform::Minds),
+            _ => Err("Unknown social media"),
+        }
+    }
+}
+
+///Reference to post on particular social media.
+#[derive(Clone, Debug)]
+pub struct PostRef {
+    ///Social media, where post is published.
+    pub platform: Platform,
+    ///Post's ID
+    pub id: PostId,
+}
+
+///Post read from social media.
+#[derive(Clone, Debug)]
+pub struct Status {
+    ///Social media, where post is published.
+    pub platform: Platform,
+    ///Post's ID
+    pub id: PostId,
+    ///Author of post
+    pub author: Account,
+    ///Text content
+    pub text: String,
+    ///Time of publishing
+    pub created_at: time::OffsetDateTime,
+    ///URLs of attached media
+    pub media: Vec<String>,
+}
+
+///Describes which page of timeline to fetch.
+///
+///IDs are specific to each social media,
+///hence it makes sense to specify them only when single social media is used.
+#[derive(Default, Clone, Debug)]
+pub struct Page {
+    ///Fetches posts older than specified ID.
+    ///
+    ///Use `Timeline::next` to continue from previous page.
+    pub max_id: Option<String>,
+    ///Fetches posts newer than specified ID.
+    ///
+    ///Ignored by Minds.
+    pub since_id: Option<String>,
+    ///Maximum number of posts to fetch.
+    ///
+    ///If not specified, social media's default is used.
+    pub limit: Option<usize>,
+}
+
+///Page of timeline
+#[derive(Default, Clone, Debug)]
+pub struct Timeline {
+    ///Fetched posts, newest first.
+    pub statuses: Vec<Status>,
+    ///Value for `Page::max_id` in order to fetch next page.
+    ///
+    ///Missing when there is nothing to fetch.
+    pub next: Option<String>,
+}
+
+///Converts HTML content into plain text.
+///
+///Line breaks and paragraphs are replaced with new lines, other tags are removed
+///and common entities are unescaped.
+pub(crate) fn html_to_text(html: &str) -> String {
+    let mut result = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(ch) = rest.chars().next() {
+        match ch {
+            '<' => {
+                let end = match rest.find('>') {
+                    Some(end) => end,
+                    None => break,
+                };
+
+                let tag = rest[1..end].trim().trim_end_matches('/').trim().to_ascii_lowercase();
+                match tag.as_str() {
+                    "br" => result.push('\n'),
+                    "/p" => result.push_str("\n\n"),
+                    _ => (),
+                }
+
+                rest = &rest[end+1..];
+            },
+            '&' => {
+                const ENTITIES: [(&str, char); 6] = [("&amp;", '&'), ("&lt;", '<'), ("&gt;", '>'), ("&quot;", '"'), ("&#39;", '\''), ("&apos;", '\'')];
+
+                match ENTITIES.iter().find(|(entity, _)| rest.starts_with(entity)) {
+                    Some((entity, ch)) => {
+                        result.push(*ch);
+                        rest = &rest[entity.len()..];
+                    },
+                    None => {
+                        result.push('&');
+                        rest = &rest[1..];
+                    }
+                }
+            },
+            ch => {
+                result.push(ch);
+                rest = &rest[ch.len_utf8()..];
+            }
+        }
+    }
+
+    let len = result.trim_end().len();
+    result.truncate(len);
+    result
+}
+
 ///Creates string of multiple hashtags
 pub fn join_hash_tags<'a, I: AsRef<str> + 'a, T: IntoIterator<Item = I>>(tags: T) -> String {
     let mut result = String::new();
@@ -6,5 +6,6 @@
 pub mod data;
 pub mod config;
 pub mod api;
+pub mod secrets;
 
 pub use api::API;
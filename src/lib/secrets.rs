@@ -0,0 +1,210 @@
+//!Encrypted storage of secrets
+//!
+//!Secrets are sealed with AES-256-GCM, using key derived from passphrase via PBKDF2-HMAC-SHA256.
+//!
+//!Layout of sealed data:
+//!
+//!- `fiesec` magic followed by format version;
+//!- Number of PBKDF2 iterations as big endian `u32`;
+//!- Salt of key derivation;
+//!- Nonce of encryption;
+//!- Encrypted secrets with authentication tag.
+//!
+//!Header is authenticated together with secrets, so it cannot be altered.
+
+use ring::{aead, pbkdf2};
+use ring::rand::{SecureRandom, SystemRandom};
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::num::NonZeroU32;
+use std::fmt;
+
+const MAGIC: &[u8] = b"fiesec\x01";
+const SALT_LEN: usize = 16;
+const HEADER_LEN: usize = MAGIC.len() + 4 + SALT_LEN + aead::NONCE_LEN;
+///Number of PBKDF2 iterations for newly sealed secrets.
+const ITERATIONS: u32 = 100_000;
+
+#[derive(Debug)]
+///Secrets errors
+pub enum SecretsError {
+    ///Data is not sealed secrets or damaged.
+    InvalidFormat,
+    ///Passphrase is wrong or secrets are altered.
+    WrongPassphrase,
+    ///Unable to generate random salt or nonce.
+    RandomFailed,
+}
+
+impl fmt::Display for SecretsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            SecretsError::InvalidFormat => "Secrets are in invalid format",
+            SecretsError::WrongPassphrase => "Unable to decrypt secrets. Passphrase is wrong or secrets are damaged",
+            SecretsError::RandomFailed => "Unable to generate random data for encryption",
+        })
+    }
+}
+
+impl Error for SecretsError {}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: NonZeroU32) -> aead::LessSafeKey {
+    let mut key = [0u8; 32];
+    pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, passphrase.as_bytes(), &mut key);
+
+    let key = aead::UnboundKey::new(&aead::AES_256_GCM, &key).expect("To create AES-256 key");
+    aead::LessSafeKey::new(key)
+}
+
+///Named secrets, such as access tokens and passwords.
+#[derive(Default, Clone)]
+pub struct Secrets {
+    entries: BTreeMap<String, String>,
+}
+
+//Values must not end up in logs
+impl fmt::Debug for Secrets {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.entries.keys()).finish()
+    }
+}
+
+impl Secrets {
+    ///Creates empty storage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Returns value of secret.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries.get(name).map(String::as_str)
+    }
+
+    ///Sets value of secret, returning previous one.
+    pub fn set(&mut self, name: String, value: String) -> Option<String> {
+        self.entries.insert(name, value)
+    }
+
+    ///Removes secret, returning its value.
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        self.entries.remove(name)
+    }
+
+    ///Returns names of all secrets.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    ///Decrypts secrets, sealed by `seal`.
+    pub fn open(data: &[u8], passphrase: &str) -> Result<Self, SecretsError> {
+        if data.len() < HEADER_LEN + aead::AES_256_GCM.tag_len() || !data.starts_with(MAGIC) {
+            return Err(SecretsError::InvalidFormat);
+        }
+
+        let (header, sealed) = data.split_at(HEADER_LEN);
+        let mut iterations = [0u8; 4];
+        iterations.copy_from_slice(&header[MAGIC.len()..MAGIC.len() + 4]);
+        let iterations = match NonZeroU32::new(u32::from_be_bytes(iterations)) {
+            Some(iterations) => iterations,
+            None => return Err(SecretsError::InvalidFormat),
+        };
+        let salt = &header[MAGIC.len() + 4..MAGIC.len() + 4 + SALT_LEN];
+        let mut nonce = [0u8; aead::NONCE_LEN];
+        nonce.copy_from_slice(&header[HEADER_LEN - aead::NONCE_LEN..]);
+
+        let key = derive_key(passphrase, salt, iterations);
+        let mut sealed = sealed.to_vec();
+        let plain = key.open_in_place(aead::Nonce::assume_unique_for_key(nonce), aead::Aad::from(header), &mut sealed)
+                       .map_err(|_| SecretsError::WrongPassphrase)?;
+
+        let plain = std::str::from_utf8(plain).map_err(|_| SecretsError::InvalidFormat)?;
+        let entries = yukikaze::serde_urlencoded::from_str(plain).map_err(|_| SecretsError::InvalidFormat)?;
+        Ok(Self { entries })
+    }
+
+    ///Encrypts secrets with key derived from `passphrase`.
+    ///
+    ///Each time new salt and nonce are used.
+    pub fn seal(&self, passphrase: &str) -> Result<Vec<u8>, SecretsError> {
+        let random = SystemRandom::new();
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; aead::NONCE_LEN];
+        random.fill(&mut salt).map_err(|_| SecretsError::RandomFailed)?;
+        random.fill(&mut nonce).map_err(|_| SecretsError::RandomFailed)?;
+
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(MAGIC);
+        header.extend_from_slice(&ITERATIONS.to_be_bytes());
+        header.extend_from_slice(&salt);
+        header.extend_from_slice(&nonce);
+
+        let iterations = NonZeroU32::new(ITERATIONS).expect("Non-zero iterations");
+        let key = derive_key(passphrase, &salt, iterations);
+        let mut sealed = yukikaze::serde_urlencoded::to_string(&self.entries).expect("To serialize secrets").into_bytes();
+        key.seal_in_place_append_tag(aead::Nonce::assume_unique_for_key(nonce), aead::Aad::from(&header), &mut sealed)
+           .expect("To encrypt secrets");
+
+        header.extend_from_slice(&sealed);
+        Ok(header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secrets() -> Secrets {
+        let mut secrets = Secrets::new();
+        secrets.set("minds_password".to_owned(), "p&ss=word %20 пароль".to_owned());
+        secrets.set("twitter_secret".to_owned(), "secret".to_owned());
+        secrets
+    }
+
+    #[test]
+    fn should_open_sealed_secrets() {
+        let sealed = secrets().seal("passphrase").expect("To seal secrets");
+        assert!(sealed.starts_with(MAGIC));
+
+        let opened = Secrets::open(&sealed, "passphrase").expect("To open secrets");
+        assert_eq!(opened.names().collect::<Vec<_>>(), ["minds_password", "twitter_secret"]);
+        assert_eq!(opened.get("minds_password"), Some("p&ss=word %20 пароль"));
+        assert_eq!(opened.get("twitter_secret"), Some("secret"));
+        assert_eq!(opened.get("gab_token"), None);
+
+        //Salt and nonce are new each time
+        assert_ne!(secrets().seal("passphrase").expect("To seal secrets"), sealed);
+    }
+
+    #[test]
+    fn should_not_open_with_wrong_passphrase() {
+        let sealed = secrets().seal("passphrase").expect("To seal secrets");
+
+        assert!(matches!(Secrets::open(&sealed, "Passphrase"), Err(SecretsError::WrongPassphrase)));
+        assert!(matches!(Secrets::open(&sealed, ""), Err(SecretsError::WrongPassphrase)));
+    }
+
+    #[test]
+    fn should_not_open_tampered_secrets() {
+        let sealed = secrets().seal("passphrase").expect("To seal secrets");
+        let tampered = |pos: usize| {
+            let mut data = sealed.clone();
+            data[pos] ^= 1;
+            Secrets::open(&data, "passphrase")
+        };
+
+        //Magic
+        assert!(matches!(tampered(0), Err(SecretsError::InvalidFormat)));
+        //Iterations, salt and nonce
+        assert!(matches!(tampered(MAGIC.len() + 3), Err(SecretsError::WrongPassphrase)));
+        assert!(matches!(tampered(MAGIC.len() + 4), Err(SecretsError::WrongPassphrase)));
+        assert!(matches!(tampered(HEADER_LEN - 1), Err(SecretsError::WrongPassphrase)));
+        //Ciphertext and tag
+        assert!(matches!(tampered(HEADER_LEN), Err(SecretsError::WrongPassphrase)));
+        assert!(matches!(tampered(sealed.len() - 1), Err(SecretsError::WrongPassphrase)));
+
+        assert!(matches!(Secrets::open(&sealed[..sealed.len() - 1], "passphrase"), Err(SecretsError::WrongPassphrase)));
+        assert!(matches!(Secrets::open(&sealed[..HEADER_LEN], "passphrase"), Err(SecretsError::InvalidFormat)));
+        assert!(matches!(Secrets::open(b"", "passphrase"), Err(SecretsError::InvalidFormat)));
+    }
+}